@@ -0,0 +1,29 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rust_advanced::pin::{OptionalSelfRef, SelfRef};
+
+// 构造开销对比：无自引用、手动两步构造（先固定再建立自引用）、new_with_ref、SelfRef
+fn constructors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("构造");
+    group.bench_function("new_no_ref", |b| {
+        b.iter(|| OptionalSelfRef::new_no_ref(black_box(42u64)))
+    });
+    group.bench_function("new_pinned + init_self_ref", |b| {
+        b.iter(|| {
+            let mut pinned = OptionalSelfRef::new_pinned(black_box(42u64));
+            pinned.as_mut().init_self_ref();
+            pinned
+        })
+    });
+    group.bench_function("new_with_ref", |b| {
+        b.iter(|| OptionalSelfRef::new_with_ref(black_box(42u64)))
+    });
+    group.bench_function("SelfRef::new", |b| {
+        b.iter(|| SelfRef::new(black_box("基准测试")))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, constructors);
+criterion_main!(benches);
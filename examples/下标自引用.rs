@@ -0,0 +1,44 @@
+use rust_advanced::pin::VecSelfRef;
+
+fn main() {
+    // ========== 场景1：扩容后地址变化，逻辑引用仍然正确 ==========
+    let mut list = VecSelfRef::new(Vec::with_capacity(2));
+    list.push("a");
+    list.push("b");
+    list.select(1);
+    let before = list.current_ptr().unwrap();
+    for word in ["c", "d", "e", "f", "g"] {
+        list.push(word); // 超过容量，触发扩容
+    }
+    let after = list.current_ptr().unwrap();
+    println!(
+        "扩容前地址: {:p}，扩容后地址: {:p}，地址变化：{}",
+        before,
+        after,
+        before != after
+    );
+    println!("当前元素仍是：{:?}", list.current());
+    assert_eq!(list.current(), Some(&"b"));
+
+    // ========== 场景2：移除元素时调整选择 ==========
+    list.remove(0); // 在选中元素之前，下标前移
+    println!(
+        "\n移除之前的元素后：{:?}，全部：{:?}",
+        list.current(),
+        list.items()
+    );
+    assert_eq!(list.current(), Some(&"b"));
+    list.remove(3); // 在选中元素之后，不受影响
+    println!("移除之后的元素后：{:?}", list.current());
+    assert_eq!(list.current(), Some(&"b"));
+    list.remove(0); // 移除的正是选中元素，选择被清除
+    println!("移除选中元素后：{:?}", list.current());
+    assert_eq!(list.current(), None);
+
+    // ========== 场景3：空 Vec ==========
+    let mut empty: VecSelfRef<i32> = VecSelfRef::new(Vec::new());
+    println!("\n空 Vec 选中 0：{:?}", empty.select(0));
+    println!("空 Vec current：{:?}", empty.current());
+    println!("空 Vec remove(0)：{:?}", empty.remove(0));
+    assert!(empty.is_empty() && empty.current_ptr().is_none());
+}
@@ -0,0 +1,37 @@
+use rust_advanced::pin::OffsetSelfRef;
+
+// 跨函数边界移动：按值传入、按值返回
+fn pass_through<T>(value: OffsetSelfRef<T>) -> OffsetSelfRef<T> {
+    value
+}
+
+fn main() {
+    // ========== 场景1：偏移自引用可以自由移动 ==========
+    let whole = OffsetSelfRef::new_with_ref(99);
+    let moved = pass_through(whole);
+    println!("移动后自引用指向：{}", moved.get_ref().unwrap());
+    assert_eq!(moved.get_ref(), Some(&99));
+
+    // ========== 场景2：String 子串视图，移动、放进 Vec 后依旧正确 ==========
+    let mut text = OffsetSelfRef::new(String::from("偏移量天然可移动"));
+    println!("\n固定子串：{:?}", text.pin_range(0..9));
+    assert_eq!(text.get_range(), Some("偏移量"));
+    println!("非字符边界：{:?}", text.pin_range(0..4));
+    assert_eq!(text.get_range(), Some("偏移量"));
+    let text = pass_through(text);
+    let mut list = vec![text.clone()];
+    list.push(text);
+    for item in &list {
+        println!("Vec 中的子串视图：{}", item.get_range().unwrap());
+        // 视图落在各自缓冲区内，而不是某个旧地址
+        assert!(core::ptr::eq(item.get_range().unwrap(), &item.data()[0..9]));
+    }
+
+    // ========== 场景3：Vec<u8> 子切片视图 ==========
+    let mut bytes = OffsetSelfRef::new(vec![1u8, 2, 3, 4, 5]);
+    println!("\n越界区间：{:?}", bytes.pin_range(3..9));
+    bytes.pin_range(1..4);
+    let bytes = pass_through(bytes);
+    println!("移动后子切片视图：{:?}", bytes.get_range().unwrap());
+    assert_eq!(bytes.get_range(), Some(&[2u8, 3, 4][..]));
+}
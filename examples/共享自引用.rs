@@ -0,0 +1,59 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use rust_advanced::pin::SharedSelfRef;
+
+// 析构计数器：验证最后一个句柄释放时数据只析构一次
+static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct Tracked;
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    // ========== 场景1：克隆句柄只增加引用计数，所有句柄指向同一地址 ==========
+    let shared = SharedSelfRef::new_with_ref(String::from("多个句柄共享的固定数据"));
+    let another = shared.clone();
+    println!("句柄1 自引用地址: {:p}", shared.get_ref().unwrap());
+    println!("句柄2 自引用地址: {:p}", another.get_ref().unwrap());
+    assert!(std::ptr::eq(shared.get_ref().unwrap(), another.get_ref().unwrap()));
+
+    // ========== 场景2：多个线程并发读取 ==========
+    let expected = shared.get_ref().unwrap() as *const String as usize;
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let handle = shared.clone();
+            thread::spawn(move || {
+                let data = handle.get_ref().unwrap();
+                // 每个线程看到的都是同一份数据
+                assert_eq!(data as *const String as usize, expected);
+                format!("线程{} 读取：{}", i, data)
+            })
+        })
+        .collect();
+    for handle in handles {
+        println!("{}", handle.join().unwrap());
+    }
+
+    // ========== 场景3：释放原句柄后，其余句柄依旧可读；最后一个句柄释放时数据只释放一次 ==========
+    drop(shared);
+    println!("原句柄释放后：{}", another.get_ref().unwrap());
+    assert_eq!(another.get_ref().unwrap(), "多个句柄共享的固定数据");
+
+    let tracked = SharedSelfRef::new_with_ref(Tracked);
+    let mut clones: Vec<_> = (0..3).map(|_| tracked.clone()).collect();
+    drop(tracked);
+    let last = clones.pop().unwrap();
+    drop(clones);
+    // 还有句柄存活：数据尚未析构，仍可通过自引用读取
+    assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+    assert!(last.get_ref().is_some());
+    drop(last);
+    println!("\n全部句柄释放后析构次数：{}", DROPS.load(Ordering::SeqCst));
+    assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+}
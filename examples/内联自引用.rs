@@ -0,0 +1,85 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rust_advanced::optional_self_ref_local;
+use rust_advanced::pin::{InlineSelfRef, OptionalSelfRef};
+
+// 计数分配器：统计堆分配次数
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// 统计执行 f 期间的堆分配次数
+fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    (result, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
+fn main() {
+    // ========== 场景1：内联存放，自引用指向结构体自身的字段 ==========
+    let pinned = InlineSelfRef::new_with_ref([1u64, 2, 3]);
+    println!("自引用指向：{:?}", pinned.get_ref().unwrap());
+    println!("自引用地址: {:p}，结构体地址: {:p}", pinned.get_ref().unwrap(), &*pinned);
+
+    // ========== 场景2：分配次数对比 ==========
+    let (inline, inline_allocs) = count_allocations(|| InlineSelfRef::new_with_ref(42u64));
+    let (boxed, boxed_allocs) = count_allocations(|| OptionalSelfRef::new_with_ref(42u64));
+    println!("\nInlineSelfRef::new_with_ref 分配次数：{}", inline_allocs);
+    println!("OptionalSelfRef::new_with_ref 分配次数：{}", boxed_allocs);
+    assert_eq!(inline_allocs, 1);
+    println!("两者读到的数据：{} / {}", inline.get_ref().unwrap(), boxed.get_ref().unwrap());
+
+    // ========== 场景3：无自引用时可自由移动，且无需分配 ==========
+    let (mut plain, plain_allocs) = count_allocations(|| InlineSelfRef::new_no_ref(7u64));
+    *plain.get_data_mut() += 1;
+    let moved = [plain];
+    println!("\nnew_no_ref 分配次数：{}，移动后数据：{}", plain_allocs, moved[0].data());
+
+    // ========== 场景4：optional_self_ref_local! 栈固定，全程没有堆分配 ==========
+    let (value, local_allocs) = count_allocations(|| {
+        optional_self_ref_local!(local = [7u64; 4]);
+        assert!(std::ptr::eq(local.get_ref().unwrap(), local.data()));
+        local.as_mut().with_mut(|data| data[0] = 8);
+        local.get_ref().unwrap()[0]
+    });
+    println!("\n栈固定实例分配次数：{}，修改后首元素：{}", local_allocs, value);
+    assert_eq!((local_allocs, value), (0, 8));
+
+    // ========== 场景5：作用域内的自引用（scoped），同样没有堆分配 ==========
+    let ((data, sum), scoped_allocs) = count_allocations(|| {
+        InlineSelfRef::scoped([1u64, 2, 3], |mut x| {
+            x.as_mut().with_mut(|data| data[2] = 30);
+            x.get_ref().unwrap().iter().sum::<u64>()
+        })
+    });
+    println!("\nscoped 交还的数据：{:?}，闭包结果：{}，分配次数：{}", data, sum, scoped_allocs);
+    assert_eq!((data, sum, scoped_allocs), ([1, 2, 30], 33, 0));
+
+    // 闭包 panic：容器在原地正常析构
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let caught = std::panic::catch_unwind(|| {
+        InlineSelfRef::scoped(String::from("panic"), |x| {
+            assert!(std::ptr::eq(x.get_ref().unwrap(), x.data()));
+            panic!("闭包中途 panic");
+        })
+    });
+    std::panic::set_hook(hook);
+    println!("panic 被捕获：{}", caught.is_err());
+    assert!(caught.is_err());
+}
@@ -0,0 +1,32 @@
+use rust_advanced::pin::SelfRefCell;
+
+fn main() {
+    // ========== 场景1：通过共享引用读取 ==========
+    let cell = SelfRefCell::new(String::from("内部可变"));
+    println!("borrow：{}，get_ref：{}", cell.borrow(), &*cell.get_ref());
+
+    // ========== 场景2：可变借用让字符串增长，守卫析构时同步 ptr ==========
+    let before = cell.borrow().as_ptr();
+    {
+        let mut guard = cell.as_ref().borrow_mut_pinned();
+        guard.push_str(&"，追加".repeat(64)); // 超出容量，缓冲区重新分配
+    }
+    let after = cell.borrow().as_ptr();
+    println!("\n缓冲区重新分配：{}，ptr 已同步：{}", before != after, cell.validate());
+    assert!(before != after);
+    assert!(cell.validate());
+    assert_eq!(cell.get_ref().as_ptr(), after);
+    println!("get_ref 长度：{}", cell.get_ref().len());
+
+    // ========== 场景3：守卫存活期间读取会 panic（同 RefCell） ==========
+    let guard = cell.as_ref().borrow_mut_pinned();
+    // 临时换成静默的 panic hook，避免打印 panic 信息
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let rejected = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| cell.get_ref().len())).is_err();
+    std::panic::set_hook(hook);
+    println!("\n守卫存活时读取被拒绝：{}", rejected);
+    assert!(rejected);
+    drop(guard);
+    println!("守卫释放后：{}", cell.get_ref().len());
+}
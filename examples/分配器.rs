@@ -0,0 +1,76 @@
+#![feature(allocator_api)]
+
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rust_advanced::pin::{InlineSelfRef, OptionalSelfRef, SelfRef};
+
+// 计数分配器：转发给 Global，并记录分配 / 释放次数
+struct Counting {
+    allocs: AtomicUsize,
+    frees: AtomicUsize,
+}
+
+unsafe impl Allocator for &'static Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.allocs.fetch_add(1, Ordering::Relaxed);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.frees.fetch_add(1, Ordering::Relaxed);
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+// 固定的实例要求分配器是 'static（同 Box::pin_in）
+static COUNTING: Counting = Counting {
+    allocs: AtomicUsize::new(0),
+    frees: AtomicUsize::new(0),
+};
+
+fn main() {
+    // ========== 场景1：容器放在自定义分配器中，自引用照常工作 ==========
+    let pinned = OptionalSelfRef::new_with_ref_in(String::from("分配器中的容器"), &COUNTING);
+    println!("new_with_ref_in：{}，自引用有效：{}", pinned.get_ref().unwrap(), pinned.is_ref_valid());
+    println!("自定义分配器分配次数：{}", COUNTING.allocs.load(Ordering::Relaxed));
+    // 只有容器本身来自 COUNTING，data 仍由全局分配器分配
+    assert_eq!(COUNTING.allocs.load(Ordering::Relaxed), 1);
+    drop(pinned);
+    assert_eq!(COUNTING.frees.load(Ordering::Relaxed), 1);
+
+    // ========== 场景2：无自引用的实例 ==========
+    let plain = OptionalSelfRef::new_no_ref_in(42, &COUNTING);
+    println!("\nnew_no_ref_in：{}，累计分配：{}", plain, COUNTING.allocs.load(Ordering::Relaxed));
+    assert_eq!(COUNTING.allocs.load(Ordering::Relaxed), 2);
+
+    // ========== 场景3：stable 入口：接管已分配好的 Box，不再复制数据 ==========
+    let owned = Box::new([7u8; 32]);
+    let addr = &*owned as *const [u8; 32];
+    let adopted = OptionalSelfRef::from_boxed_with_ref(owned);
+    println!("\nfrom_boxed_with_ref 复用原分配：{}", std::ptr::eq(addr, adopted.get_ref().unwrap()));
+    assert!(std::ptr::eq(addr, adopted.get_ref().unwrap()));
+
+    // ========== 场景4：SelfRef 固定在自定义分配器中 ==========
+    let allocs_before = COUNTING.allocs.load(Ordering::Relaxed);
+    let text = SelfRef::new_in("分配器中的 SelfRef", &COUNTING);
+    println!("\nSelfRef::new_in：{}，ptr 与缓冲区一致：{}", text.get_ref(), text.validate());
+    // 恰好一次：SelfRef 结构体本身；String 的缓冲区来自全局分配器
+    assert_eq!(COUNTING.allocs.load(Ordering::Relaxed) - allocs_before, 1);
+    assert!(text.validate());
+    let frees_before = COUNTING.frees.load(Ordering::Relaxed);
+    drop(text);
+    assert_eq!(COUNTING.frees.load(Ordering::Relaxed) - frees_before, 1);
+    assert!(SelfRef::try_new_in("", &COUNTING).is_err());
+
+    // ========== 场景5：InlineSelfRef 的全部分配都来自自定义分配器 ==========
+    let (allocs_before, frees_before) = (COUNTING.allocs.load(Ordering::Relaxed), COUNTING.frees.load(Ordering::Relaxed));
+    let inline = InlineSelfRef::new_with_ref_in([1u64, 2, 3], &COUNTING);
+    println!("\nInlineSelfRef::new_with_ref_in：{:?}", inline.get_ref().unwrap());
+    // 数据内联在容器中：唯一的一次分配来自 COUNTING
+    assert_eq!(COUNTING.allocs.load(Ordering::Relaxed) - allocs_before, 1);
+    assert!(std::ptr::eq(inline.get_ref().unwrap(), inline.data()));
+    drop(inline);
+    assert_eq!(COUNTING.frees.load(Ordering::Relaxed) - frees_before, 1);
+}
@@ -0,0 +1,34 @@
+use rust_advanced::pin::{RangeError, SelfRefSlice};
+
+fn main() {
+    // ========== 场景1：window 指向 data 的子区间 ==========
+    let mut slice = SelfRefSlice::new(vec![10, 20, 30, 40, 50], 1..3).unwrap();
+    println!("完整数据：{:?}", slice.data());
+    println!("窗口：{:?}", slice.get_window());
+    println!("窗口地址: {:p}，data[1] 地址: {:p}", slice.get_window().as_ptr(), &slice.data()[1]);
+    assert_eq!(slice.get_window(), &[20, 30]);
+    // 窗口就是 data 缓冲区中的那一段，而不是拷贝
+    assert!(std::ptr::eq(slice.get_window(), &slice.data()[1..3]));
+
+    // ========== 场景2：固定后重新选择窗口 ==========
+    slice.as_mut().set_window(2..5).unwrap();
+    println!("\n新窗口：{:?}", slice.get_window());
+    assert_eq!(slice.get_window(), &[30, 40, 50]);
+    assert!(std::ptr::eq(slice.get_window(), &slice.data()[2..5]));
+
+    // 空窗口同样落在缓冲区内
+    slice.as_mut().set_window(5..5).unwrap();
+    assert!(slice.get_window().is_empty());
+    assert_eq!(slice.get_window().as_ptr(), slice.data()[5..].as_ptr());
+    slice.as_mut().set_window(2..5).unwrap();
+
+    // ❌ 越界区间被拒绝，原窗口保持不变
+    let err = slice.as_mut().set_window(4..9).unwrap_err();
+    println!("越界 set_window：{}", err);
+    assert_eq!(err, RangeError::OutOfBounds { start: 4, end: 9, len: 5 });
+    println!("保持原窗口：{:?}", slice.get_window());
+    assert!(std::ptr::eq(slice.get_window(), &slice.data()[2..5]));
+    let err = SelfRefSlice::new(vec![1, 2], 0..3).unwrap_err();
+    println!("越界 new：{}", err);
+    assert_eq!(err, RangeError::OutOfBounds { start: 0, end: 3, len: 2 });
+}
@@ -0,0 +1,30 @@
+use std::sync::OnceLock;
+use std::thread;
+
+use rust_advanced::pin::OptionalSelfRef;
+
+// 全局单例：泄漏的自引用实例，引用永久有效
+static CONFIG: OnceLock<&'static String> = OnceLock::new();
+
+fn config() -> &'static String {
+    CONFIG.get_or_init(|| OptionalSelfRef::leak(OptionalSelfRef::new_with_ref(String::from("全局配置"))))
+}
+
+fn main() {
+    // ========== 场景1：leak 交出 &'static T ==========
+    let leaked: &'static i32 = OptionalSelfRef::leak(OptionalSelfRef::new_with_ref(42));
+    println!("leak 后读取：{}", leaked);
+    assert_eq!(*leaked, 42);
+
+    // 尚未建立自引用的实例也可以泄漏：leak 会先建立自引用
+    let deferred = OptionalSelfRef::leak(OptionalSelfRef::new_pinned(String::from("延迟建立")));
+    assert_eq!(deferred, "延迟建立");
+
+    // ========== 场景2：全局单例，多个线程读取同一份数据 ==========
+    let addrs: Vec<usize> = (0..4)
+        .map(|_| thread::spawn(|| config() as *const String as usize))
+        .map(|handle| handle.join().unwrap())
+        .collect();
+    println!("单例内容：{}，所有线程看到同一地址：{}", config(), addrs.iter().all(|&a| a == addrs[0]));
+    assert!(addrs.iter().all(|&addr| addr == config() as *const String as usize));
+}
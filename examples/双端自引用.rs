@@ -0,0 +1,50 @@
+use rust_advanced::pin::{RangeError, SelfRefPair};
+
+// head 与 tail 必须首尾相接、覆盖整个 data，且都落在 data 的缓冲区内
+fn assert_views(pair: &SelfRefPair, split: usize) {
+    let data = pair.data();
+    assert_eq!((pair.head_str(), pair.tail_str()), data.split_at(split));
+    assert_eq!(pair.head_str().as_ptr(), data.as_ptr());
+    assert_eq!(pair.tail_str().as_ptr(), data[split..].as_ptr());
+}
+
+fn main() {
+    // ========== 场景1：在字符边界处切分 ==========
+    let mut pair = SelfRefPair::new("你好world", 6).unwrap();
+    println!("完整数据：{}", pair.data());
+    println!("head：{}，tail：{}", pair.head_str(), pair.tail_str());
+    println!("tail 地址: {:p}，data[6] 地址: {:p}", pair.tail_str().as_ptr(), &pair.data().as_bytes()[6]);
+    assert_eq!((pair.head_str(), pair.tail_str()), ("你好", "world"));
+    assert_views(&pair, 6);
+
+    // 另一个内部切分点
+    pair.as_mut().resplit(3).unwrap();
+    assert_eq!((pair.head_str(), pair.tail_str()), ("你", "好world"));
+    assert_views(&pair, 3);
+
+    // ========== 场景2：边界情况——切分点为 0 与 len ==========
+    pair.as_mut().resplit(0).unwrap();
+    println!("\nsplit = 0：head = {:?}，tail = {:?}", pair.head_str(), pair.tail_str());
+    assert_eq!((pair.head_str(), pair.tail_str()), ("", "你好world"));
+    assert_views(&pair, 0);
+    let len = pair.data().len();
+    pair.as_mut().resplit(len).unwrap();
+    println!("split = len：head = {:?}，tail = {:?}", pair.head_str(), pair.tail_str());
+    assert_eq!((pair.head_str(), pair.tail_str()), ("你好world", ""));
+    assert_views(&pair, len);
+
+    // ❌ 不在字符边界上 / 越界的切分点被拒绝，原视图保持不变
+    let (head, tail) = (pair.head_str() as *const str, pair.tail_str() as *const str);
+    let err = pair.as_mut().resplit(1).unwrap_err();
+    println!("\n切在汉字中间：{}", err);
+    assert_eq!(err, RangeError::NotCharBoundary { index: 1 });
+    let err = pair.as_mut().resplit(len + 1).unwrap_err();
+    println!("越界：{}", err);
+    assert_eq!(err, RangeError::OutOfBounds { start: 0, end: len + 1, len });
+    println!("保持原视图：head = {:?}，tail = {:?}", pair.head_str(), pair.tail_str());
+    assert!(std::ptr::eq(pair.head_str(), head) && std::ptr::eq(pair.tail_str(), tail));
+    assert_views(&pair, len);
+    let err = SelfRefPair::new("abc", 4).unwrap_err();
+    println!("越界 new：{}", err);
+    assert_eq!(err, RangeError::OutOfBounds { start: 0, end: 4, len: 3 });
+}
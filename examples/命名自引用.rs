@@ -0,0 +1,41 @@
+use rust_advanced::pin::{NamedSelfRef, RangeError};
+
+fn main() {
+    // ========== 场景1：一份 URL，三个具名视图 ==========
+    let url = "https://example.com/路径/页面?q=固定";
+    let mut named = NamedSelfRef::new(url);
+    let host_start = "https://".len();
+    let path_start = host_start + "example.com".len();
+    let query_start = url.find('?').unwrap() + 1;
+    named.as_mut().name_range("host", host_start..path_start).unwrap();
+    named.as_mut().name_range("path", path_start..query_start - 1).unwrap();
+    named.as_mut().name_range("query", query_start..url.len()).unwrap();
+
+    println!("视图：{:?}", named.names().collect::<Vec<_>>());
+    for name in named.names() {
+        println!("  {} → {}", name, named.get_named(name).unwrap());
+    }
+    assert_eq!(named.get_named("host"), Some("example.com"));
+    assert_eq!(named.get_named("path"), Some("/路径/页面"));
+    assert_eq!(named.get_named("query"), Some("q=固定"));
+    assert_eq!(named.get_named("fragment"), None);
+    // 视图都指向同一个固定的缓冲区
+    let buffer = named.data().as_bytes().as_ptr_range();
+    assert!(named.names().all(|name| buffer.contains(&named.get_named(name).unwrap().as_ptr())));
+
+    // ========== 场景2：区间校验 ==========
+    // "路" 占 3 个字节，从它的中间切开不在字符边界上
+    let inside_char = path_start + 2;
+    let err = named.as_mut().name_range("broken", inside_char..query_start).unwrap_err();
+    println!("\n不在字符边界上：{}", err);
+    assert_eq!(err, RangeError::NotCharBoundary { index: inside_char });
+    assert!(named.as_mut().name_range("broken", 0..1000).is_err());
+    assert_eq!(named.names().count(), 3);
+
+    // ========== 场景3：替换为更短的内容：仍在范围内的视图保留，其余移除 ==========
+    named.as_mut().update_data("https://example.org");
+    println!("\n替换后：{:?}，host → {:?}", named.names().collect::<Vec<_>>(), named.get_named("host"));
+    assert_eq!(named.names().collect::<Vec<_>>(), ["host"]);
+    assert_eq!(named.get_named("host"), Some("example.org"));
+    assert_eq!(named.get_named("query"), None);
+}
@@ -0,0 +1,365 @@
+use std::pin::Pin;
+
+use rust_advanced::pin::{AppendOutcome, OptionalSelfRef, RangeError, SelfRef, SelfRefError, WithRef};
+#[cfg(all(debug_assertions, feature = "test-utils"))]
+use rust_advanced::pin::InvariantViolation;
+
+fn main() {
+    let mut pinned_sr = SelfRef::new("Rust Pin 终极修正版：解决 DST 薄指针问题");
+    
+    // 1. 打印核心地址：结构体地址 + String 内部缓冲区地址 + ptr 目标，以及内容
+    let description = pinned_sr.describe();
+    println!("📌 {}", description);
+    // 内容在最后，可能包含分隔符，因此最多切成 4 段
+    let parts: Vec<&str> = description.splitn(4, '，').collect();
+    assert_eq!(parts[0], format!("结构体地址: {:p}", pinned_sr.get_struct_addr()));
+    assert_eq!(parts[1], format!("缓冲区地址: {:p}", pinned_sr.data().as_ptr()));
+    assert_eq!(parts[2], format!("ptr 目标: {:p}", pinned_sr.get_ref().as_ptr()));
+    assert_eq!(parts[3], format!("内容: {}", pinned_sr.data()));
+    println!("📌 ptr 指向内容: {}", pinned_sr.get_ref());
+    println!("📌 字节长度: {}，字符数: {}，是否为空: {}",
+        pinned_sr.len(), pinned_sr.get_ref().chars().count(), pinned_sr.is_empty());
+    println!("📌 前 4 个字节: {:?}", &pinned_sr.as_bytes()[..4]);
+
+    // 2. 修改 data 并同步自引用
+    pinned_sr.as_mut().update_data("Pin 核心：固定结构体地址，不固定字段内部地址");
+    println!("\n🔄 修改后 ——");
+    println!("🔄 SelfRef 结构体地址: {:p}", pinned_sr.get_struct_addr()); // 地址不变！
+    println!("🔄 String 内部缓冲区地址: {:p}", pinned_sr.data().as_ptr()); // 地址变化！
+    println!("🔄 ptr 指向的地址: {:p}", pinned_sr.get_ref().as_ptr()); // 同步变化，指向新缓冲区
+    println!("🔄 修改后 data: {}", pinned_sr.data());
+    println!("🔄 修改后 ptr 指向: {}", pinned_sr.get_ref());
+
+    // 3. 追加内容：追加足够多的字节触发缓冲区重新分配，指针随之同步
+    let before = pinned_sr.data().as_ptr();
+    pinned_sr.as_mut().push_str(&"，追加".repeat(64));
+    println!("\n➕ 追加后 ——");
+    println!("➕ 缓冲区是否重新分配: {}", before != pinned_sr.data().as_ptr());
+    println!("➕ ptr 指向的地址: {:p}", pinned_sr.get_ref().as_ptr());
+    println!("➕ 追加后 ptr 指向内容长度: {}", pinned_sr.get_ref().len());
+    println!("➕ ptr 与缓冲区一致: {}", pinned_sr.validate());
+
+    // 3.1 原地替换：容量足够时复用缓冲区，地址不变；超出容量才重新分配
+    let before = pinned_sr.data().as_ptr();
+    pinned_sr.as_mut().update_in_place("原地替换的内容");
+    println!("\n♻️ 原地替换后缓冲区地址不变: {}", before == pinned_sr.data().as_ptr());
+    println!("♻️ ptr 指向的地址: {:p}，内容: {}", pinned_sr.get_ref().as_ptr(), pinned_sr.get_ref());
+    assert_eq!(before, pinned_sr.data().as_ptr());
+    assert_eq!(before, pinned_sr.get_ref().as_ptr());
+    let capacity = pinned_sr.capacity();
+    pinned_sr.as_mut().update_in_place(&"超出容量".repeat(capacity));
+    println!("♻️ 超出容量后重新分配: {}，ptr 与缓冲区一致: {}",
+        before != pinned_sr.data().as_ptr(), pinned_sr.validate());
+    assert!(pinned_sr.validate());
+
+    // 3.2 闭包访问：with_ref 把结果累积到捕获的变量
+    let mut seen = Vec::new();
+    for (i, text) in ["一", "二", "三"].into_iter().enumerate() {
+        pinned_sr.as_mut().update_data(text);
+        let len = pinned_sr.with_ref(|s| {
+            seen.push(format!("{}:{}", i, s));
+            s.len()
+        });
+        assert_eq!(len, text.len());
+    }
+    println!("\n🔎 with_ref 累积: {:?}", seen);
+    assert_eq!(seen, ["0:一", "1:二", "2:三"]);
+
+    // 4. 克隆：克隆体拥有独立的缓冲区和指针，原实例释放后依旧可读
+    let cloned = pinned_sr.as_ref().clone_pinned();
+    println!("\n📋 克隆体与原实例相等: {}", cloned == pinned_sr);
+    println!("📋 克隆体 ptr 指向的地址: {:p}", cloned.get_ref().as_ptr());
+    assert_eq!(cloned.get_ref(), pinned_sr.get_ref());
+    assert_ne!(cloned.get_ref().as_ptr(), pinned_sr.get_ref().as_ptr());
+    let expected = pinned_sr.get_ref().to_owned();
+    drop(pinned_sr);
+    println!("📋 原实例释放后克隆体内容长度: {}", cloned.get_ref().len());
+    assert_eq!(cloned.get_ref(), expected);
+
+    // 5. 用完后取回 String：最后一次 update_data 的内容原样交还
+    let mut last = SelfRef::new("旧内容");
+    last.as_mut().update_data("最后一次更新");
+    let owned = SelfRef::into_string(last);
+    assert_eq!(owned, "最后一次更新");
+    println!("\n📦 取回的 String: {}", owned);
+
+    // 6. 内置的地址报告：{:#} 输出结构体、缓冲区、ptr 目标地址
+    let report = SelfRef::new("地址报告");
+    println!("\n🧭 {}", report);
+    println!("🧭 {:#}", report);
+    let text = format!("{:#}", report);
+    assert_eq!(field(&text, "缓冲区地址"), field(&text, "ptr 目标"));
+
+    // 7. 校验构造：空字符串被拒绝
+    println!("\n✅ try_new(\"非空\"): {}", SelfRef::try_new("非空").unwrap());
+    println!("❌ try_new(\"\"): {}", SelfRef::try_new("").unwrap_err());
+
+    // 8. 与 OptionalSelfRef 互相转换：数据搬到新的分配，指针在新位置上重建
+    let source = SelfRef::new("往返转换");
+    let optional: Pin<Box<OptionalSelfRef<String, WithRef>>> = source.into();
+    println!("\n🔁 SelfRef → OptionalSelfRef: {:?}，自引用有效: {}", optional.get_ref(), optional.is_ref_valid());
+    let back: Pin<Box<SelfRef>> = optional.try_into().unwrap();
+    println!("🔁 OptionalSelfRef → SelfRef: {}，ptr 一致: {}", back.get_ref(), back.validate());
+    assert_eq!(back.get_ref(), "往返转换");
+    let empty = OptionalSelfRef::new_with_ref(String::new());
+    println!("🔁 空字符串无法转换: {}", Pin::<Box<SelfRef>>::try_from(empty).unwrap_err());
+
+    // 8.1 标准转换：&str / String → Pin<Box<SelfRef>> → get_ref → &str
+    let from_str: Pin<Box<SelfRef>> = "来自 &str".try_into().unwrap();
+    assert_eq!(from_str.get_ref(), "来自 &str");
+    let owned = String::from("来自 String");
+    let buffer = owned.as_ptr();
+    let from_string = Pin::<Box<SelfRef>>::try_from(owned).unwrap();
+    println!("\n🔁 TryFrom<&str>: {}，TryFrom<String>: {}，缓冲区未复制: {}",
+        from_str.get_ref(), from_string.get_ref(), buffer == from_string.get_ref().as_ptr());
+    assert_eq!(buffer, from_string.get_ref().as_ptr());
+    println!("🔁 空 String: {}", Pin::<Box<SelfRef>>::try_from(String::new()).unwrap_err());
+    assert_eq!(Pin::<Box<SelfRef>>::try_from("").unwrap_err(), SelfRefError::Empty);
+
+    // 9. 析构前校验（debug 构建）：经过各种修改后 ptr 仍与缓冲区一致，析构时的断言不会触发
+    #[cfg(debug_assertions)]
+    {
+        let mut checked = SelfRef::new("析构校验");
+        checked.as_mut().update_data("更新");
+        checked.as_mut().push_str(&"追加".repeat(32));
+        assert!(checked.validate());
+        drop(checked);
+        println!("\n🧹 析构前校验通过");
+    }
+
+    // 10. Debug 输出地址与不变量状态
+    let healthy = SelfRef::new("调试输出");
+    let text = format!("{:?}", healthy);
+    println!("\n🔍 {}", text);
+    assert!(text.contains("invariant_ok: true, in_buffer: true"));
+    #[cfg(all(debug_assertions, feature = "test-utils"))]
+    {
+        // ptr 偏移到缓冲区中间：仍在缓冲区内，但不变量被破坏
+        let mut debugged = SelfRef::new("调试输出");
+        unsafe { debugged.as_mut().debug_retarget_ptr("调试".len().."调试输出".len()) };
+        let text = format!("{:?}", debugged);
+        println!("🔍 {}", text);
+        assert!(text.contains("invariant_ok: false, in_buffer: true"));
+        // 重新同步后才能安全析构
+        debugged.as_mut().update_data("已修复");
+        assert!(format!("{:?}", debugged).contains("invariant_ok: true"));
+    }
+
+    // 11. verify_invariant：不变量被破坏时给出期望值与实际值
+    println!("\n🩺 健康实例: {:?}", healthy.verify_invariant());
+    assert_eq!(healthy.verify_invariant(), Ok(()));
+    #[cfg(all(debug_assertions, feature = "test-utils"))]
+    {
+        let mut broken = SelfRef::new("不变量校验");
+        let buffer = broken.data().as_ptr() as usize;
+        let len = broken.len();
+
+        // 起始地址偏移
+        unsafe { broken.as_mut().debug_retarget_ptr(3..len) };
+        let violation = broken.verify_invariant().unwrap_err();
+        println!("🩺 地址偏移: {}", violation);
+        assert_eq!(violation, InvariantViolation::AddressMismatch { expected: buffer, actual: buffer + 3 });
+
+        // 起始地址正确，长度不对
+        unsafe { broken.as_mut().debug_retarget_ptr(0..6) };
+        let violation = broken.verify_invariant().unwrap_err();
+        println!("🩺 长度不符: {}", violation);
+        assert_eq!(violation, InvariantViolation::LengthMismatch { expected: len, actual: 6 });
+
+        // 任何修改方法都会重新同步，结束时 debug_verify! 的校验随之通过
+        broken.as_mut().push_str("，已修复");
+        assert_eq!(broken.verify_invariant(), Ok(()));
+    }
+
+    // 12. Send：固定的 Box 整个移动到子线程，在那里通过自引用读取
+    let travelling = SelfRef::new("跨线程的 SelfRef");
+    let struct_addr = travelling.get_struct_addr() as usize;
+    let handle = std::thread::spawn(move || {
+        let text = travelling.get_ref().to_string();
+        // Box 只是换了线程，堆上的结构体没有移动
+        assert_eq!(travelling.get_struct_addr() as usize, struct_addr);
+        assert!(travelling.validate(), "子线程中自引用仍然有效");
+        text
+    });
+    let received = handle.join().unwrap();
+    println!("🧵 子线程读取到: {}", received);
+    assert_eq!(received, "跨线程的 SelfRef");
+
+    // 13. 按空白切分：每个词元都是固定缓冲区中的子切片
+    let sentence = SelfRef::new("  a  bb ccc ");
+    let tokens: Vec<&str> = sentence.split_whitespace_pinned().collect();
+    println!("✂️ 词元: {:?}", tokens);
+    assert_eq!(tokens, ["a", "bb", "ccc"]);
+    let buffer = sentence.as_bytes().as_ptr_range();
+    assert!(tokens.iter().all(|token| buffer.contains(&token.as_ptr())));
+
+    // 14. 两阶段构造：new_uninit → 写入内容 → init
+    let mut staged = SelfRef::new_uninit();
+    println!("🧩 未初始化: try_get_ref = {:?}, is_init = {}", staged.try_get_ref(), staged.is_init());
+    assert_eq!(staged.try_get_ref(), None);
+    // 初始化之前的修改不会建立自引用
+    staged.as_mut().push_str("两阶段");
+    assert!(!staged.is_init());
+    staged.as_mut().init();
+    println!("🧩 init 之后: try_get_ref = {:?}", staged.try_get_ref());
+    assert_eq!(staged.try_get_ref(), Some("两阶段"));
+    assert_eq!(staged.get_ref(), "两阶段");
+    assert!(staged.validate());
+
+    // 未初始化时 get_ref panic，信息中包含结构体地址
+    let uninit = SelfRef::new_uninit();
+    let addr = format!("{:p}", uninit.get_struct_addr());
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let payload = std::panic::catch_unwind(|| uninit.get_ref().len()).unwrap_err();
+    std::panic::set_hook(hook);
+    let message = payload.downcast_ref::<String>().expect("panic 信息是格式化的 String");
+    println!("🧩 get_ref panic: {}", message);
+    assert!(message.contains("尚未初始化") && message.contains(&addr));
+
+    // 15. 只固定一段子串（set_range）：每个汉字占 3 个字节
+    let mut ranged = SelfRef::new("固定一段子串");
+    ranged.as_mut().set_range(6..12).expect("区间有效");
+    println!("🎯 子串 6..12: {}", ranged.get_ref());
+    assert_eq!(ranged.get_ref(), "一段");
+    assert!(ranged.validate());
+
+    // 越界、不在字符边界上：返回错误，原有区间不变
+    let out_of_bounds = ranged.as_mut().set_range(6..100).unwrap_err();
+    println!("🎯 越界: {}", out_of_bounds);
+    assert_eq!(out_of_bounds, RangeError::OutOfBounds { start: 6, end: 100, len: 18 });
+    let split_char = ranged.as_mut().set_range(1..6).unwrap_err();
+    println!("🎯 字符中间: {}", split_char);
+    assert_eq!(split_char, RangeError::NotCharBoundary { index: 1 });
+    assert_eq!(ranged.get_ref(), "一段");
+
+    // update_data：区间在新内容上仍有效则重新应用，否则恢复为整个字符串
+    ranged.as_mut().update_data("区间仍然有效的内容");
+    println!("🎯 update_data 后仍有效: {}", ranged.get_ref());
+    assert_eq!((ranged.get_ref(), ranged.range()), ("仍然", Some(6..12)));
+    ranged.as_mut().update_data("短");
+    println!("🎯 update_data 后失效: {}", ranged.get_ref());
+    assert_eq!((ranged.get_ref(), ranged.range()), ("短", None));
+
+    // push_str 只追加，区间始终保留
+    ranged.as_mut().set_range(0..3).unwrap();
+    ranged.as_mut().push_str("，再追加很长的一段内容触发重新分配");
+    assert_eq!(ranged.get_ref(), "短");
+    assert!(ranged.validate());
+    ranged.as_mut().clear_range();
+    assert_eq!(ranged.get_ref(), ranged.data());
+
+    // 16. find_and_pin：拥有整段文本，只保留感兴趣部分的视图
+    let mut haystack = SelfRef::new("日志：连接超时，正在重试");
+    assert!(haystack.as_mut().find_and_pin("超时"));
+    println!("🔍 匹配到: {}，区间: {:?}", haystack.get_ref(), haystack.range());
+    // 多字节模式：区间以字节计，长度正好是匹配的子串
+    assert_eq!((haystack.get_ref(), haystack.range()), ("超时", Some(15..21)));
+
+    // 没有匹配：原有的视图不变
+    assert!(!haystack.as_mut().find_and_pin("成功"));
+    assert_eq!(haystack.get_ref(), "超时");
+
+    // 修改内容后自动重新查找
+    haystack.as_mut().update_data("再次超时");
+    println!("🔍 update_data 后重新查找: {:?}", haystack.range());
+    assert_eq!((haystack.get_ref(), haystack.range()), ("超时", Some(6..12)));
+    // 匹配消失：恢复为整个字符串，模式保留，内容再次包含时自动重新指向
+    haystack.as_mut().update_data("已恢复");
+    println!("🔍 匹配消失后: {}，模式: {:?}", haystack.get_ref(), haystack.pattern());
+    assert_eq!((haystack.get_ref(), haystack.range()), ("已恢复", None));
+    haystack.as_mut().push_str("，又超时");
+    assert_eq!(haystack.get_ref(), "超时");
+    assert!(haystack.validate());
+
+    // 17. new 接受 impl Into<String>：已有的 String 直接移入，缓冲区不复制
+    let owned = String::from("已经拥有的 String");
+    let owned_buffer = owned.as_ptr();
+    let adopted = SelfRef::new(owned);
+    println!("📦 移入 String 后缓冲区地址不变: {}", adopted.data().as_ptr() == owned_buffer);
+    assert_eq!(adopted.data().as_ptr(), owned_buffer);
+    // 构造完成时 ptr 已指向固定位置上的缓冲区
+    for pinned in [adopted, SelfRef::new("复制的 &str")] {
+        assert_eq!(pinned.get_ref().as_ptr(), pinned.data().as_ptr());
+    }
+
+    // 18. push_str：只在缓冲区重新分配时修正子串视图的 ptr
+    let mut log = SelfRef::new(String::with_capacity(64) + "头部");
+    log.as_mut().set_range(0..6).unwrap();
+    let view = log.get_ref().as_ptr();
+    // 预留了容量：原地追加，子串视图原封不动
+    assert_eq!(log.as_mut().push_str("，原地追加"), AppendOutcome::InPlace);
+    assert_eq!(log.as_mut().push_str(""), AppendOutcome::InPlace);
+    println!("📝 原地追加后视图: {}，地址不变: {}", log.get_ref(), log.get_ref().as_ptr() == view);
+    assert_eq!((log.get_ref(), log.get_ref().as_ptr()), ("头部", view));
+
+    // 反复追加直到超出容量：报告重新分配，视图随之指向新缓冲区
+    let capacity = log.capacity();
+    let outcomes: Vec<_> = (0..capacity).map(|_| log.as_mut().push_str("++")).collect();
+    let reallocations = outcomes.iter().filter(|o| **o == AppendOutcome::Reallocated).count();
+    println!("📝 追加 {} 次，重新分配 {} 次，视图: {}", outcomes.len(), reallocations, log.get_ref());
+    assert!(reallocations >= 1);
+    assert_eq!(log.get_ref(), "头部");
+    assert_eq!(log.get_ref().as_ptr(), log.data().as_ptr());
+    assert!(log.validate());
+
+    // 指向整个字符串时原地追加也要更新长度
+    let mut whole = SelfRef::new(String::with_capacity(32) + "整体");
+    assert_eq!(whole.as_mut().push_str("视图"), AppendOutcome::InPlace);
+    assert_eq!(whole.get_ref(), "整体视图");
+
+    // 19. 紧密循环中更新固定的记录：update_in_place 复用缓冲区
+    let mut record = SelfRef::new("一条较长的初始记录内容");
+    let buffer = record.data().as_ptr();
+    for i in 0..10 {
+        record.as_mut().update_in_place(&format!("记录{}", i));
+    }
+    println!("🔁 更新 10 次后: {}，缓冲区地址不变: {}，容量: {}",
+        record.get_ref(), record.data().as_ptr() == buffer, record.capacity());
+    // 更短的内容：缓冲区地址完全相同，get_ref 返回新内容
+    assert_eq!(record.data().as_ptr(), buffer);
+    assert_eq!((record.get_ref(), record.get_ref().as_ptr()), ("记录9", buffer));
+    // 复用计数只在 debug 构建中维护
+    println!("🔁 缓冲区复用次数: {}", record.buffer_reused_count());
+    assert_eq!(record.buffer_reused_count(), if cfg!(debug_assertions) { 10 } else { 0 });
+
+    // 20. 逐字符遍历固定的内容：多字节字符按字符计数，偏移以字节计
+    let mixed = SelfRef::new("Pin 固定🦀");
+    let count = mixed.chars_pinned().count();
+    let last = mixed.char_indices_pinned().last();
+    println!("🔤 字符数: {}，字节数: {}，最后一个字符: {:?}", count, mixed.len(), last);
+    assert_eq!((count, mixed.len()), (7, 14));
+    assert_eq!(last, Some((10, '🦀')));
+
+    // 21. 读取守卫（view）：每次解引用前校验 ptr，不同步时 debug 构建下立即 panic
+    let viewed = SelfRef::new("守卫读取");
+    {
+        let view = viewed.view();
+        println!("\n🛡️ view: {}，长度: {}", view, view.len());
+        assert_eq!(&*view, "守卫读取");
+        assert!(view.starts_with("守卫"));
+    }
+    #[cfg(all(debug_assertions, feature = "test-utils"))]
+    {
+        // 模拟不同步：ptr 偏移到缓冲区中间
+        let mut viewed = SelfRef::new("守卫读取");
+        unsafe { viewed.as_mut().debug_retarget_ptr(3..6) };
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        // 实例移入闭包：panic 后在 unwind 中析构，析构不再二次 panic 中止进程
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || viewed.view().len()))
+            .unwrap_err();
+        std::panic::set_hook(hook);
+        let message = payload.downcast_ref::<String>().expect("panic 信息是格式化的 String");
+        println!("🛡️ 不同步时 view panic: {}", message);
+        assert!(message.contains("不同步"));
+    }
+}
+
+// 从多行报告中取出「标签: 值」的值
+fn field<'a>(report: &'a str, label: &str) -> &'a str {
+    report
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(label)?.strip_prefix(": "))
+        .expect("报告中缺少该字段")
+}
@@ -0,0 +1,46 @@
+use rust_advanced::pin::MultiSelfRef;
+
+fn main() {
+    // ========== 场景1：三个自引用，移除中间一个 ==========
+    let mut multi = MultiSelfRef::new(vec![1, 2, 3]);
+    let first = multi.as_mut().add_ref();
+    let middle = multi.as_mut().add_ref();
+    let last = multi.as_mut().add_ref();
+    println!("新增三个自引用：{:?} / {:?} / {:?}", first, middle, last);
+
+    assert!(multi.as_mut().remove_ref(middle));
+    let data_addr = multi.data() as *const Vec<i32>;
+    for id in [first, last] {
+        let target = multi.get(id).unwrap() as *const Vec<i32>;
+        println!("{:?} 仍指向数据：{:p}（数据地址 {:p}）", id, target, data_addr);
+        assert_eq!(target, data_addr);
+    }
+    println!("已移除的句柄：{:?}", multi.get(middle));
+    assert!(multi.get(middle).is_none());
+
+    // ========== 场景2：槽位复用，旧句柄不会误指向新自引用 ==========
+    let reused = multi.as_mut().add_ref();
+    println!("\n复用槽位的新句柄：{:?}，旧句柄仍无效：{}", reused, multi.get(middle).is_none());
+    assert!(multi.get(reused).is_some() && multi.get(middle).is_none());
+    println!("有效自引用：{}，遍历：{:?}", multi.ref_count(),
+        multi.iter_refs().map(|(id, _)| id).collect::<Vec<_>>());
+    assert_eq!(multi.iter_refs().count(), 3);
+
+    // ========== 场景3：String 的子串视图 ==========
+    let mut text = MultiSelfRef::new(String::from("固定的字符串视图"));
+    let head = text.as_mut().add_range_ref(0..6).unwrap();
+    let tail = text.as_mut().add_range_ref(18..24).unwrap();
+    let whole = text.as_mut().add_ref();
+    println!("\n子串视图：{:?} / {:?}，整体：{:?}", text.get_range(head), text.get_range(tail), text.get(whole));
+    assert_eq!(text.get_range(head), Some("固定"));
+    assert_eq!(text.get_range(tail), Some("视图"));
+    println!("非字符边界：{}", text.as_mut().add_range_ref(0..1).unwrap_err());
+    text.as_mut().remove_ref(head);
+    assert_eq!(text.get_range(tail), Some("视图"));
+
+    // ========== 场景4：Vec<u8> 的子切片视图 ==========
+    let mut bytes = MultiSelfRef::new(b"pinned bytes".to_vec());
+    let word = bytes.as_mut().add_range_ref(7..12).unwrap();
+    println!("\n子切片视图：{:?}", bytes.get_range(word).map(String::from_utf8_lossy));
+    println!("越界：{}", bytes.as_mut().add_range_ref(7..20).unwrap_err());
+}
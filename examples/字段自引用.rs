@@ -0,0 +1,34 @@
+use rust_advanced::pin::MappedSelfRef;
+
+#[derive(Debug)]
+struct Config {
+    name: String,
+    retries: u32,
+}
+
+fn main() {
+    // ========== 场景1：自引用指向结构体的 name 字段 ==========
+    let cfg = Config {
+        name: String::from("固定配置"),
+        retries: 3,
+    };
+    let pinned = MappedSelfRef::new_with_ref_map(cfg, |c| c.name.as_str());
+    println!("完整数据：{:?}", pinned.data());
+    println!("自引用指向 name：{}", pinned.get_ref().unwrap());
+    println!("name 缓冲区地址: {:p}", pinned.data().name.as_ptr());
+    println!("自引用指向地址: {:p}", pinned.get_ref().unwrap().as_ptr());
+    println!("retries：{}", pinned.data().retries);
+    assert_eq!(pinned.get_ref(), Some("固定配置"));
+    // 自引用指向 data 内 name 的缓冲区本身，而不是一份拷贝
+    assert!(core::ptr::eq(pinned.get_ref().unwrap(), pinned.data().name.as_str()));
+    assert_eq!(pinned.data().retries, 3);
+
+    // ========== 场景2：自引用指向元组的一个元素 ==========
+    let pair = MappedSelfRef::new_with_ref_map((1, String::from("第二个元素")), |p| &p.1);
+    println!("\n自引用指向元组 .1：{}", pair.get_ref().unwrap());
+    assert!(core::ptr::eq(pair.get_ref().unwrap(), &pair.data().1));
+
+    // ❌ 闭包无法返回指向局部变量的引用（HRTB 签名拒绝，编译报错，注释掉；由 new_with_ref_map 的 compile_fail 文档测试覆盖）
+    // let local = String::from("局部变量");
+    // let bad = MappedSelfRef::new_with_ref_map(0, |_| local.as_str());
+}
@@ -0,0 +1,59 @@
+use rust_advanced::pin::{RangeError, SelfRefBytes};
+
+fn main() {
+    // ========== 场景1：二进制内容，ptr 指向自身的缓冲区 ==========
+    let bytes = SelfRefBytes::new(vec![0xde, 0xad, 0xbe, 0xef]);
+    println!("🧱 自引用指向：{:02x?}", bytes.get_ref());
+    println!("结构体地址: {:p}，缓冲区地址: {:p}", &*bytes, bytes.data().as_ptr());
+    assert_eq!(bytes.get_ref(), [0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(bytes.get_ref().as_ptr(), bytes.data().as_ptr());
+    assert_eq!(bytes.as_str_checked(), None);
+
+    // ========== 场景2：空缓冲区同样可以固定 ==========
+    let mut empty = SelfRefBytes::new(Vec::new());
+    println!("\n🫙 空缓冲区：{:?}，长度 {}", empty.get_ref(), empty.len());
+    assert!(empty.is_empty() && empty.get_ref().is_empty());
+    assert_eq!(empty.as_str_checked(), Some(""));
+    assert!(empty.as_mut().set_range(0..0).is_ok());
+    assert_eq!(empty.as_mut().set_range(0..1), Err(RangeError::OutOfBounds { start: 0, end: 1, len: 0 }));
+    assert!(empty.validate());
+
+    // ========== 场景3：区间取到两端：开头、结尾的空区间与整个缓冲区 ==========
+    let mut ranged = SelfRefBytes::new(&b"0123456789"[..]);
+    for range in [0..0, 10..10, 0..10, 0..1, 9..10] {
+        ranged.as_mut().set_range(range.clone()).expect("区间有效");
+        println!("🎯 区间 {:?}: {:?}", range, ranged.as_str_checked());
+        assert_eq!(ranged.get_ref(), &b"0123456789"[range]);
+        assert!(ranged.validate());
+    }
+    let err = ranged.as_mut().set_range(5..11).unwrap_err();
+    println!("❌ 越界区间：{}", err);
+    assert_eq!(ranged.range(), Some(9..10));
+    ranged.as_mut().clear_range();
+    assert_eq!(ranged.get_ref().len(), 10);
+
+    // ========== 场景4：更新后内容短于区间，恢复为整个缓冲区 ==========
+    let mut shrinking = SelfRefBytes::new(&b"header:payload"[..]);
+    shrinking.as_mut().set_range(7..14).expect("区间有效");
+    assert_eq!(shrinking.get_ref(), b"payload");
+    shrinking.as_mut().update_data(b"header:body!!!");
+    println!("\n✂️ 等长更新保留区间：{:?}", shrinking.as_str_checked());
+    assert_eq!(shrinking.get_ref(), b"body!!!");
+    shrinking.as_mut().update_data(b"short");
+    println!("✂️ 缩短后：{:?}，区间 {:?}", shrinking.as_str_checked(), shrinking.range());
+    assert_eq!(shrinking.range(), None);
+    assert_eq!(shrinking.get_ref(), b"short");
+    shrinking.as_mut().update_data(b"");
+    assert!(shrinking.get_ref().is_empty() && shrinking.validate());
+
+    // ========== 场景5：UTF-8 检查：合法与非法各一次 ==========
+    let mut text = SelfRefBytes::new("字节里的文字".as_bytes());
+    println!("\n🔤 合法 UTF-8：{:?}", text.as_str_checked());
+    assert_eq!(text.as_str_checked(), Some("字节里的文字"));
+    // 区间切在多字节字符中间：字节仍可读，但不是合法的 UTF-8
+    text.as_mut().set_range(1..6).expect("字节区间不受字符边界限制");
+    println!("🔤 切在字符中间：{:02x?} → {:?}", text.get_ref(), text.as_str_checked());
+    assert_eq!(text.as_str_checked(), None);
+    text.as_mut().set_range(3..6).expect("区间有效");
+    assert_eq!(text.as_str_checked(), Some("节"));
+}
@@ -0,0 +1,69 @@
+use rust_advanced::pin::{DeserializedSelfRef, OptionalSelfRef, SelfRef, SelfRefDef};
+
+fn main() {
+    // ========== 场景1：无自引用 → 反序列化为可移动实例 ==========
+    let no_ref = OptionalSelfRef::new_no_ref(42);
+    let json = serde_json::to_string(&no_ref).unwrap();
+    println!("无自引用序列化：{}", json);
+    match serde_json::from_str::<DeserializedSelfRef<i32>>(&json).unwrap() {
+        DeserializedSelfRef::Movable(movable) => println!("反序列化为可移动实例：{}", movable),
+        DeserializedSelfRef::Pinned(_) => unreachable!(),
+    }
+
+    // ========== 场景2：有自引用 → 反序列化为固定实例，指针指向新分配 ==========
+    let with_ref = OptionalSelfRef::new_with_ref(String::from("持久化的自引用"));
+    let json = serde_json::to_string(&*with_ref).unwrap();
+    println!("\n有自引用序列化：{}", json);
+    match serde_json::from_str::<DeserializedSelfRef<String>>(&json).unwrap() {
+        DeserializedSelfRef::Pinned(pinned) => {
+            println!("反序列化为固定实例：{}", pinned);
+            println!("原实例自引用地址: {:p}", with_ref.get_ref().unwrap());
+            println!("新实例自引用地址: {:p}", pinned.get_ref().unwrap());
+            println!("新实例自引用指向：{}", pinned.get_ref().unwrap());
+        }
+        DeserializedSelfRef::Movable(_) => unreachable!(),
+    }
+
+    // ========== 场景3：SelfRef 保存内容与子串偏移，反序列化时重建 ptr ==========
+    let sr = SelfRef::new("序列化的 SelfRef");
+    let json = serde_json::to_string(&*sr).unwrap();
+    println!("\nSelfRef 序列化：{}", json);
+    assert_eq!(json, r#"{"data":"序列化的 SelfRef","offset":0,"len":20,"init":true}"#);
+    let SelfRefDef(restored) = serde_json::from_str(&json).unwrap();
+    println!("反序列化后 ptr 指向：{}", restored.get_ref());
+    println!("ptr 与新缓冲区一致：{}", restored.get_ref().as_ptr() == restored.data().as_ptr());
+    assert_eq!(restored.get_ref(), sr.get_ref());
+    assert_eq!(restored.range(), None);
+
+    // ========== 场景4：子串区间按偏移往返，指针落在新缓冲区内 ==========
+    let mut ranged = SelfRef::new("前缀|子串|后缀");
+    ranged.as_mut().set_range(7..13).expect("区间有效");
+    let json = serde_json::to_string(&*ranged).unwrap();
+    println!("\n子串序列化：{}", json);
+    let SelfRefDef(restored) = serde_json::from_str(&json).unwrap();
+    println!("反序列化后 ptr 指向：{}", restored.get_ref());
+    assert_eq!(restored.get_ref(), "子串");
+    assert_eq!(restored.range(), Some(7..13));
+    let buffer = restored.data().as_bytes().as_ptr_range();
+    assert!(buffer.contains(&restored.get_ref().as_ptr()));
+    assert_ne!(restored.get_ref().as_ptr(), ranged.get_ref().as_ptr());
+
+    // ========== 场景5：清空后的实例同样可以往返 ==========
+    let mut emptied = SelfRef::new("即将清空");
+    emptied.as_mut().update_data("");
+    let json = serde_json::to_string(&*emptied).unwrap();
+    println!("\n空内容序列化：{}", json);
+    let SelfRefDef(restored) = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.get_ref(), "");
+    assert!(restored.is_init() && restored.validate());
+
+    // ========== 场景6：损坏的偏移 → 反序列化报错而不是得到错误的指针 ==========
+    for corrupted in [
+        r#"{"data":"前缀","offset":3,"len":6,"init":true}"#,
+        r#"{"data":"前缀","offset":1,"len":2,"init":true}"#,
+        r#"{"data":"前缀","offset":18446744073709551615,"len":2,"init":true}"#,
+    ] {
+        let err = serde_json::from_str::<SelfRefDef>(corrupted).unwrap_err();
+        println!("❌ {} → {}", corrupted, err);
+    }
+}
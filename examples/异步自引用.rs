@@ -0,0 +1,72 @@
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::task::{Context, Poll, Waker};
+
+use rust_advanced::pin::{OptionalSelfRef, SelfRefFuture};
+
+// 手写的计数 Future：前两次 poll 返回 Pending，第三次返回 Ready(次数)
+#[derive(Debug)]
+struct Countdown {
+    polls: u32,
+}
+
+impl Future for Countdown {
+    type Output = u32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        self.polls += 1;
+        if self.polls < 3 {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        } else {
+            Poll::Ready(self.polls)
+        }
+    }
+}
+
+// 极简执行器：用空操作的 Waker 反复 poll，直到 Future 完成
+fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut polls = 0;
+    loop {
+        polls += 1;
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return (output, polls);
+        }
+    }
+}
+
+fn main() {
+    // ========== 场景1：手动 poll，观察 Pending → Ready ==========
+    let mut future = pin!(SelfRefFuture::new("跨越 await 的借用"));
+    let mut cx = Context::from_waker(Waker::noop());
+    println!("第一次 poll：{:?}", future.as_mut().poll(&mut cx));
+    println!("第二次 poll：{:?}", future.as_mut().poll(&mut cx));
+
+    // ========== 场景2：交给执行器驱动 ==========
+    let (len, polls) = block_on(SelfRefFuture::new("hello"));
+    println!("\n执行器得到长度：{}，共 poll {} 次", len, polls);
+
+    // ========== 场景3：Pin<&mut SelfRefFuture> 本身也是 Future ==========
+    let mut pinned = Box::pin(SelfRefFuture::new("固定在堆上"));
+    let (len, _) = block_on(pinned.as_mut());
+    println!("通过 Pin<&mut _> 驱动，长度：{}", len);
+
+    // ========== 场景4：OptionalSelfRef 包装 Future，直接 await ==========
+    let mut wrapped = OptionalSelfRef::new_with_ref(Countdown { polls: 0 });
+    let (output, polls) = block_on(wrapped.as_mut());
+    println!("\nOptionalSelfRef<Countdown> 输出：{}，共 poll {} 次", output, polls);
+    assert_eq!((output, polls), (3, 3));
+    println!("完成后自引用仍可用：{:?}，有效：{}", wrapped.get_ref(), wrapped.is_ref_valid());
+    assert_eq!(wrapped.get_ref().unwrap().polls, 3);
+
+    // 包装 !Unpin 的 async 块状态机
+    let text = String::from("async 块");
+    let future = OptionalSelfRef::new_with_ref(async move {
+        let view = text.as_str();
+        std::future::ready(()).await;
+        view.len()
+    });
+    println!("async 块输出：{}", block_on(future).0);
+}
@@ -0,0 +1,47 @@
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use rust_advanced::pin::OptionalSelfRef;
+
+fn main() {
+    // ========== 场景1：升级后的句柄指向同一地址 ==========
+    let owner = OptionalSelfRef::new_arc_with_ref(String::from("被观察的数据"));
+    let weak = OptionalSelfRef::downgrade(&owner);
+    let original = owner.get_ref().unwrap() as *const String;
+    let upgraded = weak.upgrade().expect("拥有者仍然存活");
+    let observed = upgraded.get_ref().unwrap() as *const String;
+    println!("原始地址: {:p}，升级后地址: {:p}", original, observed);
+    assert_eq!(original, observed);
+    drop(upgraded);
+
+    // ========== 场景2：拥有者释放后升级失败 ==========
+    println!("\n释放前：{:?}", weak);
+    drop(owner);
+    println!("释放后：{:?}，upgrade：{:?}", weak, weak.upgrade().map(|_| ()));
+    assert!(weak.upgrade().is_none());
+
+    // ========== 场景3：写者释放的同时，读者不断升级 ==========
+    let owner = OptionalSelfRef::new_arc_with_ref(vec![1u64, 2, 3]);
+    let barrier = Arc::new(Barrier::new(5));
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let weak = OptionalSelfRef::downgrade(&owner);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                let mut hits = 0;
+                while let Some(strong) = weak.upgrade() {
+                    // 升级成功时自引用必然有效
+                    assert!(strong.is_ref_valid());
+                    assert_eq!(strong.get_ref().unwrap().iter().sum::<u64>(), 6);
+                    hits += 1;
+                }
+                hits
+            })
+        })
+        .collect();
+    barrier.wait();
+    drop(owner);
+    let hits: Vec<usize> = readers.into_iter().map(|h| h.join().unwrap()).collect();
+    println!("\n写者释放后所有读者都退出，各自成功升级次数：{:?}", hits);
+}
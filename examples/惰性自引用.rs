@@ -0,0 +1,25 @@
+use rust_advanced::pin::OnceSelfRef;
+
+fn main() {
+    // ========== 场景1：首次访问时建立自引用 ==========
+    let pinned = OnceSelfRef::new(String::from("惰性初始化"));
+    println!("访问前已建立：{}", pinned.is_initialized());
+    assert!(!pinned.is_initialized());
+
+    let first = pinned.as_ref().get_or_init_ref();
+    println!("首次访问：{}，地址：{:p}", first, first);
+    assert!(pinned.is_initialized());
+    assert!(std::ptr::eq(first, pinned.data()));
+
+    // ========== 场景2：再次访问复用同一个指针 ==========
+    let second = pinned.as_ref().get_or_init_ref();
+    println!("再次访问：{}，地址：{:p}", second, second);
+    assert!(std::ptr::eq(first, second));
+
+    // ========== 场景3：只需要共享引用，多个读者可同时持有结果 ==========
+    let shared = OnceSelfRef::new(vec![1, 2, 3]);
+    let a = shared.as_ref().get_or_init_ref();
+    let b = shared.as_ref().get_or_init_ref();
+    println!("\n两个读者：{:?} / {:?}，同一地址：{}", a, b, std::ptr::eq(a, b));
+    assert!(std::ptr::eq(a, b));
+}
@@ -0,0 +1,27 @@
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+
+use rust_advanced::pin::{ProjectedSelfRef, SelfRefFuture};
+
+fn main() {
+    // ========== 场景1：经投影修改数据，自引用自动同步 ==========
+    let mut counter = ProjectedSelfRef::new_with_ref(vec![1, 2, 3]);
+    counter.as_mut().with_data_mut(|mut v| v.push(4));
+    println!("自引用指向：{:?}", counter.get_ref().unwrap());
+    assert_eq!(counter.get_ref().unwrap(), &[1, 2, 3, 4]);
+
+    // ========== 场景2：投影出的 Pin<&mut T> 可以直接 poll 内部的 !Unpin Future ==========
+    let mut wrapped = ProjectedSelfRef::new_with_ref(SelfRefFuture::new("投影后 poll"));
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut polls = 0;
+    let len = loop {
+        polls += 1;
+        // 公开的投影直接交出 Pin<&mut SelfRefFuture>
+        if let Poll::Ready(len) = wrapped.as_mut().project_data().data().poll(&mut cx) {
+            break len;
+        }
+    };
+    println!("\n内部 Future 完成：长度 {}，共 poll {} 次", len, polls);
+    assert_eq!(len, "投影后 poll".len());
+    assert_eq!(polls, 2);
+}
@@ -0,0 +1,30 @@
+use rust_advanced::pin::SelfRefMap;
+
+fn main() {
+    // ========== 场景1：touch 设置热点，热点指向映射内部 ==========
+    let mut cache = SelfRefMap::new();
+    cache.as_mut().insert("alpha", String::from("第一项"));
+    cache.as_mut().insert("beta", String::from("第二项"));
+    println!("touch(\"alpha\")：{:?}", cache.as_mut().touch("alpha"));
+    let hot = cache.get_cached().unwrap() as *const String;
+    let entry = cache.get("alpha").unwrap() as *const String;
+    println!("热点地址: {:p}，映射中的值: {:p}", hot, entry);
+    assert_eq!(hot, entry);
+
+    // ========== 场景2：insert 可能重哈希，热点被清除 ==========
+    for i in 0..64 {
+        cache.as_mut().insert(if i % 2 == 0 { "gamma" } else { "delta" }, i.to_string());
+    }
+    println!("\n多次插入后热点：{:?}", cache.get_cached());
+    assert!(cache.get_cached().is_none());
+    cache.as_mut().touch("alpha");
+    println!("重新 touch 后：{:?}", cache.get_cached());
+    assert!(std::ptr::eq(cache.get_cached().unwrap(), cache.get("alpha").unwrap()));
+
+    // ========== 场景3：remove 清除热点；touch 不存在的键 ==========
+    cache.as_mut().remove("alpha");
+    println!("\n移除热点键后：{:?}", cache.get_cached());
+    assert!(cache.get_cached().is_none());
+    println!("touch 不存在的键：{:?}", cache.as_mut().touch("omega"));
+    println!("剩余 {} 项", cache.len());
+}
@@ -0,0 +1,43 @@
+use rust_advanced::pin::{BuildError, OptionalSelfRefBuilder, SelfRefBuilder, SelfRefError};
+
+fn main() {
+    // ========== 场景1：可移动实例 ==========
+    let movable = OptionalSelfRefBuilder::new(1).with_display_name("cfg").build_movable().unwrap();
+    println!("build_movable：{}", movable);
+    println!("显示名称：{:?}，自引用：{:?}", movable.display_name(), movable.get_ref());
+
+    // ========== 场景2：固定实例，按配置决定是否建立自引用 ==========
+    let pinned = OptionalSelfRefBuilder::new(String::from("固定"))
+        .with_self_ref(true)
+        .with_display_name("带自引用")
+        .build_pinned()
+        .unwrap();
+    println!("\nbuild_pinned（自引用）：{}", pinned);
+    println!("自引用指向：{:?}", pinned.get_ref());
+    let without_ref = OptionalSelfRefBuilder::new(2).build_pinned().unwrap();
+    println!("build_pinned（无自引用）：{}", without_ref);
+
+    // ❌ 不合法的组合返回错误
+    let err = OptionalSelfRefBuilder::new(3).with_self_ref(true).build_movable().unwrap_err();
+    assert_eq!(err, BuildError::SelfRefNeedsPinning);
+    println!("\n要求自引用却 build_movable：{}", err);
+    let err = OptionalSelfRefBuilder::new(4).with_display_name("").build_pinned().unwrap_err();
+    assert_eq!(err, BuildError::EmptyDisplayName);
+    println!("空的显示名称：{}", err);
+
+    // ========== 场景3：SelfRefBuilder，build 时才推导指针 ==========
+    let built = SelfRefBuilder::new().data("构建器").build();
+    println!("\nSelfRefBuilder：{}，ptr 一致：{}", built.get_ref(), built.validate());
+
+    // 预留容量：之后的追加不会重新分配缓冲区
+    let mut reserved = SelfRefBuilder::new().data("abc").with_capacity(64).build();
+    let buffer = reserved.data().as_ptr();
+    println!("预留容量：{}", reserved.capacity());
+    reserved.as_mut().push_str(&"x".repeat(60));
+    println!("追加 60 字节后缓冲区未重新分配：{}", buffer == reserved.data().as_ptr());
+    assert_eq!(buffer, reserved.data().as_ptr());
+
+    // ❌ 没有内容
+    assert_eq!(SelfRefBuilder::new().try_build().unwrap_err(), SelfRefError::Empty);
+    println!("没有内容：{}", SelfRefBuilder::new().with_capacity(8).try_build().unwrap_err());
+}
@@ -0,0 +1,17 @@
+use rust_advanced::pin::SelfRefGeneric;
+
+fn main() {
+    // ========== 场景1：i32 ==========
+    let mut number = SelfRefGeneric::new(41);
+    let before = number.get_ref() as *const i32;
+    number.as_mut().update(42);
+    println!("i32：{}，替换前后地址相同：{}", number.get_ref(), std::ptr::eq(before, number.get_ref()));
+    assert_eq!(*number.get_ref(), 42);
+
+    // ========== 场景2：Vec<u8> ==========
+    let mut bytes = SelfRefGeneric::new(vec![1u8, 2, 3]);
+    println!("\nVec<u8>：{:?}", bytes.get_ref());
+    bytes.as_mut().update(vec![9; 1024]);
+    println!("替换后长度：{}，首元素：{}", bytes.get_ref().len(), bytes.get_ref()[0]);
+    assert_eq!(bytes.get_ref().len(), 1024);
+}
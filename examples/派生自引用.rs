@@ -0,0 +1,53 @@
+use core::marker::PhantomPinned;
+use rust_advanced::{self_referential, SelfReferential};
+
+/// 由派生宏生成 new / get_ref 的自引用结构体
+#[derive(SelfReferential)]
+struct Article {
+    #[payload]
+    body: String,
+    #[self_ref]
+    body_ptr: *const String,
+    // 其余字段以 Default::default() 初始化
+    views: u32,
+    _pin: PhantomPinned,
+}
+
+#[derive(SelfReferential)]
+struct Samples<T> {
+    #[payload]
+    values: Vec<T>,
+    #[self_ref]
+    ptr: *mut Vec<T>,
+    _pin: PhantomPinned,
+}
+
+/// 属性宏自动插入 `_pin: PhantomPinned`，无需手写
+#[self_referential]
+struct Note {
+    #[payload]
+    text: String,
+    #[self_ref]
+    text_ptr: *const String,
+}
+
+fn main() {
+    // ========== 场景1：派生宏生成的 new 直接得到 Pin<Box<_>> ==========
+    let article = Article::new(String::from("派生宏生成的自引用"));
+    println!("📄 自引用指向：{}", article.get_ref());
+    println!("结构体地址: {:p}，数据地址: {:p}，自引用: {:p}", &*article, &article.body, article.body_ptr);
+    assert!(core::ptr::eq(article.body_ptr, &article.body));
+    assert_eq!(article.views, 0);
+
+    // ========== 场景2：泛型结构体与 *mut 自引用 ==========
+    let samples = Samples::new(vec![1.5, 2.5, 3.5]);
+    println!("\n📊 泛型实例自引用指向：{:?}", samples.get_ref());
+    assert_eq!(samples.get_ref().len(), 3);
+    assert!(core::ptr::eq(samples.ptr, &samples.values));
+
+    // ========== 场景3：属性宏插入 PhantomPinned ==========
+    let note = Note::new(String::from("属性宏补上了 PhantomPinned"));
+    println!("\n📝 属性宏实例自引用指向：{}", note.get_ref());
+    assert!(core::ptr::eq(note.text_ptr, &note.text));
+    let _: &PhantomPinned = &note._pin;
+}
@@ -0,0 +1,32 @@
+use rust_advanced::{optional_self_ref_local, selfref};
+
+selfref!(
+    /// 由宏生成的自引用字节缓冲区
+    MyBuf,
+    Vec<u8>
+);
+
+selfref!(Title, String);
+
+fn main() {
+    // ========== 场景1：宏生成的类型，new 直接得到 Pin<Box<_>> ==========
+    let mut buf = MyBuf::new(vec![1, 2, 3]);
+    println!("自引用指向：{:?}", buf.get_ref());
+    println!("结构体地址: {:p}，自引用地址: {:p}", &*buf, buf.get_ref());
+
+    // ========== 场景2：替换数据后自引用自动同步 ==========
+    buf.as_mut().update(vec![4, 5, 6, 7]);
+    println!("替换后自引用指向：{:?}", buf.get_ref());
+
+    let title = Title::new(String::from("宏生成的自引用字符串"));
+    println!("\nTitle 自引用指向：{}", title.get_ref());
+
+    // ========== 场景3：栈固定的内联实例，无需 Box::pin ==========
+    optional_self_ref_local!(local = String::from("栈上固定"));
+    println!("\n栈固定实例自引用指向：{}", local.get_ref().unwrap());
+    println!("栈固定实例地址: {:p}", &*local);
+    assert!(std::ptr::eq(local.get_ref().unwrap(), local.data()));
+    local.as_mut().with_mut(|s| s.push_str("，可修改"));
+    println!("修改后：{}", local.get_ref().unwrap());
+    assert_eq!(local.get_ref().unwrap(), "栈上固定，可修改");
+}
@@ -0,0 +1,26 @@
+use rust_advanced::pin::{SelfRef, SelfRefStack};
+
+fn main() {
+    // ========== 场景1：压入三个自引用实例，记录各自的结构体地址 ==========
+    let mut stack = SelfRefStack::new();
+    let mut addrs = Vec::new();
+    for text in ["第一层", "第二层", "第三层"] {
+        let item = SelfRef::new(text);
+        addrs.push(item.get_struct_addr());
+        stack.push(item);
+    }
+    println!("📚 压入 {} 个实例，栈顶：{}", stack.len(), stack.peek().unwrap().get_ref());
+
+    // ========== 场景2：按 LIFO 顺序弹出，每个实例的自引用依然有效 ==========
+    for expected in ["第三层", "第二层", "第一层"] {
+        let item = stack.pop().expect("栈中还有实例");
+        println!("⬆️ 弹出：{}，自引用有效：{}", item.get_ref(), item.validate());
+        assert_eq!(item.get_ref(), expected);
+        assert!(item.validate());
+        // 弹出的是原来那个固定的 Box，结构体没有移动
+        assert_eq!(Some(item.get_struct_addr()), addrs.pop());
+    }
+    assert!(stack.is_empty());
+    assert!(stack.pop().is_none());
+    println!("✅ 栈已清空");
+}
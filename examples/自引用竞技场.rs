@@ -0,0 +1,48 @@
+use rust_advanced::pin::SelfRefArena;
+
+fn main() {
+    // ========== 场景1：许多短字符串共享一块预留好的缓冲区 ==========
+    let mut arena = SelfRefArena::with_capacity(64 * 1024);
+    let first = arena.push("第一个词元");
+    let first_addr = arena.get(first).unwrap().as_ptr();
+    for i in 0..1000 {
+        arena.push(&format!("token{}", i));
+    }
+    println!("存入 {} 个字符串，块数：{}", arena.len(), arena.chunk_count());
+    println!("首个句柄仍然有效：{}", arena.get(first).unwrap());
+    println!("首个字符串地址未变：{}", first_addr == arena.get(first).unwrap().as_ptr());
+    println!("句柄 500：{:?}", arena.get(500));
+    println!("无效句柄：{:?}", arena.get(10_000));
+    assert_eq!((arena.len(), arena.chunk_count()), (1001, 1));
+    assert_eq!(arena.get(first), Some("第一个词元"));
+    assert_eq!(arena.get(first).unwrap().as_ptr(), first_addr);
+    assert_eq!(arena.get(500), Some("token499"));
+    assert_eq!(arena.get(10_000), None);
+    // 同一块内的字符串首尾相接，第二个紧跟在第一个之后
+    assert_eq!(arena.get(1).unwrap().as_ptr(), first_addr.wrapping_add("第一个词元".len()));
+
+    // ========== 场景2：容量不足时另开新块，旧块不会重新分配 ==========
+    let mut small = SelfRefArena::with_capacity(8);
+    let handles: Vec<_> = ["abc", "defgh", "ijklmnop", "一段超过块容量的长字符串"]
+        .iter()
+        .map(|s| small.push(s))
+        .collect();
+    println!("\n小块竞技场块数：{}", small.chunk_count());
+    for handle in &handles {
+        println!("句柄 {} → {}", handle, small.get(*handle).unwrap());
+    }
+    // "abc" 与 "defgh" 恰好填满首块；其余两个各自另开新块
+    assert_eq!(small.chunk_count(), 3);
+    let views: Vec<_> = handles.iter().map(|&h| small.get(h).unwrap()).collect();
+    assert_eq!(views, ["abc", "defgh", "ijklmnop", "一段超过块容量的长字符串"]);
+    assert_eq!(views[1].as_ptr(), views[0].as_ptr().wrapping_add(3));
+
+    // 继续存入不会移动已有的字符串
+    let before: Vec<_> = views.iter().map(|v| v.as_ptr()).collect();
+    for i in 0..100 {
+        small.push(&format!("{}", i));
+    }
+    for (&handle, &addr) in handles.iter().zip(&before) {
+        assert_eq!(small.get(handle).unwrap().as_ptr(), addr);
+    }
+}
@@ -0,0 +1,510 @@
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashSet};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::thread;
+
+use rust_advanced::pin::{live_self_ref_count, NoRef, OptionalSelfRef, PlainBox, RebindError, WithRef};
+#[cfg(all(debug_assertions, feature = "test-utils"))]
+use rust_advanced::pin::InvariantViolation;
+
+// 编译期断言：T 实现了 Unpin
+fn assert_unpin<T: Unpin>() {}
+
+// 编译期断言：T 可以跨线程移动和共享
+fn assert_send_sync<T: Send + Sync>() {}
+
+// 编译期断言：T 未实现某个 trait（歧义 impl 技巧：T 若实现了该 trait，两个 impl 同时适用 → 推断失败，编译报错）
+macro_rules! assert_not_impl {
+    ($t:ty: $tr:path) => {{
+        trait AmbiguousIfImpl<A> {
+            fn some_item() {}
+        }
+        impl<T: ?Sized> AmbiguousIfImpl<()> for T {}
+        #[allow(dead_code)]
+        struct Invalid;
+        impl<T: ?Sized + $tr> AmbiguousIfImpl<Invalid> for T {}
+        let _ = <$t as AmbiguousIfImpl<_>>::some_item;
+    }};
+}
+
+// 泛型代码中按 Default 构造两种状态
+fn make_plain<T: Default>() -> PlainBox<T> {
+    PlainBox::default()
+}
+
+fn make_pinned<T: Default>() -> Pin<Box<OptionalSelfRef<T, WithRef>>> {
+    OptionalSelfRef::default_pinned()
+}
+
+// 泛型适配：任何能转换成 PlainBox 的值
+fn wrap<T: ?Sized, X: Into<PlainBox<T>>>(value: X) -> PlainBox<T> {
+    value.into()
+}
+
+fn main() {
+    // ========== 场景0：类型状态的编译期检查 ==========
+    assert_unpin::<OptionalSelfRef<i32, NoRef>>();
+    assert_unpin::<PlainBox<String>>();
+    assert_not_impl!(OptionalSelfRef<i32, WithRef>: Unpin);
+    assert_send_sync::<OptionalSelfRef<String, WithRef>>();
+    assert_not_impl!(OptionalSelfRef<Rc<i32>, WithRef>: Send);
+    assert_not_impl!(OptionalSelfRef<Cell<i32>, NoRef>: Sync);
+
+    // ========== 场景1：无自引用 → Unpin → 自由移动、解除固定 ==========
+    println!("=== 无自引用的情况（Unpin）===");
+    let mut no_ref = OptionalSelfRef::new_no_ref(42);
+    println!("初始实例：{}", no_ref);
+
+    // ✅ Unpin 类型可直接 Pin::new（无需 Box::pin）
+    let pinned_no_ref = Pin::new(&mut no_ref);
+    println!("Pin 后的实例：{}", pinned_no_ref);
+
+    // ✅ Unpin 类型可解除固定
+    let unpinned = Pin::into_inner(pinned_no_ref);
+    // ✅ Unpin 类型可自由移动
+    let moved_no_ref = unpinned;
+    println!("移动后的实例：{}", moved_no_ref);
+
+    // ========== 场景2：有自引用 → !Unpin → 必须 Box::pin，无法移动 ==========
+    println!("\n=== 有自引用的情况（!Unpin）===");
+    let with_ref = OptionalSelfRef::new_with_ref(99);
+    println!("Pin<Box> 实例：{}", with_ref);
+    println!("自引用指向的值：{}", with_ref.get_ref().unwrap());
+
+    // ❌ WithRef 状态为 !Unpin：Pin::into_inner、mem::swap、Pin::new 都无法编译，
+    //    见 WithRef 文档中的 compile_fail 测试
+
+    // ❌ 仍有自引用时 into_unpinned 失败，固定的 Box 原样交还
+    let mut with_ref = match OptionalSelfRef::into_unpinned(with_ref) {
+        Ok(_) => unreachable!("仍有自引用，不应解除固定"),
+        Err(still_pinned) => still_pinned,
+    };
+    println!("into_unpinned 被拒绝，实例仍固定：{}", with_ref);
+
+    // ✅ 先清除自引用，再安全解除固定（无需 unsafe）
+    with_ref.as_mut().clear_self_ref();
+    let unpinned = OptionalSelfRef::into_unpinned(with_ref).expect("已清除自引用");
+    println!("清除自引用后解除固定的实例：{}", unpinned);
+
+    // ========== 场景3：先构造、自由移动，固定后再建立自引用 ==========
+    println!("\n=== 固定后再建立自引用（init_self_ref）===");
+    let deferred = OptionalSelfRef::new_no_ref(String::from("延迟建立")).into_with_ref();
+    // ✅ 尚未建立自引用，固定之前仍可移动
+    let moved_deferred = deferred;
+    let mut pinned_deferred = std::pin::pin!(moved_deferred);
+    println!("固定后、建立前：{}", pinned_deferred);
+    let first = pinned_deferred.as_mut().init_self_ref();
+    let second = pinned_deferred.as_mut().init_self_ref();
+    println!("第一次 init_self_ref：{}，第二次：{}", first, second);
+    println!("建立后：{}，自引用指向：{}", pinned_deferred, pinned_deferred.get_ref().unwrap());
+    assert!(first && !second);
+    assert!(pinned_deferred.with_data_and_ref(|data, view| view.is_some_and(|v| std::ptr::eq(data, v))));
+
+    // ✅ 也可以先得到固定的 Box，做完其他工作后再 init_self_ref（幂等）
+    let mut pinned_later = OptionalSelfRef::new_pinned(7);
+    println!("new_pinned：{}", pinned_later);
+    pinned_later.as_mut().init_self_ref();
+    pinned_later.as_mut().init_self_ref();
+    println!("init_self_ref 两次后：{}", pinned_later);
+    assert_eq!(pinned_later.get_ref(), Some(&7));
+
+    // ✅ clear_ref：清除自引用并取回可移动的 PlainBox
+    let plain: PlainBox<i32> = OptionalSelfRef::clear_ref(pinned_later);
+    let moved_plain = plain;
+    println!("clear_ref 后移动的实例：{}", moved_plain);
+
+    // ✅ upgrade：可移动的实例一步固定并建立自引用
+    let upgraded = moved_plain.upgrade();
+    println!("upgrade 后：{}，自引用有效：{}", upgraded, upgraded.is_ref_valid());
+
+    // ========== 场景4：通过结构化投影修改数据，自引用保持有效 ==========
+    println!("\n=== 结构化投影修改数据（get_data_mut）===");
+    let mut counter = OptionalSelfRef::new_with_ref(1);
+    *counter.as_mut().get_data_mut() += 41;
+    println!("修改 i32 后：{}，自引用指向：{}", counter, counter.get_ref().unwrap());
+    *counter.as_mut().get_mut_data() -= 40;
+    println!("get_mut_data 修改后：{}，自引用指向：{}", counter, counter.get_ref().unwrap());
+    assert_eq!(counter.get_ref(), Some(&2));
+
+    let mut text = OptionalSelfRef::new_with_ref(String::from("固定"));
+    text.as_mut().get_data_mut().push_str("的字符串，追加后 Box 地址不变");
+    println!("修改 String 后：自引用指向：{}", text.get_ref().unwrap());
+    assert!(text.is_ref_valid());
+
+    // ✅ 无自引用的实例直接交出 &mut T
+    let mut plain = OptionalSelfRef::new_no_ref(0);
+    *plain.get_data_mut() = 10;
+    println!("PlainBox 修改后：{}", plain);
+    assert_eq!(plain, OptionalSelfRef::new_no_ref(10));
+
+    // ========== 场景5：克隆时针对新数据重建自引用 ==========
+    println!("\n=== 克隆（clone_pinned）===");
+    let original = OptionalSelfRef::new_with_ref(String::from("被克隆的数据"));
+    let cloned = original.as_ref().clone_pinned();
+    println!("原实例自引用地址: {:p}", original.get_ref().unwrap());
+    println!("克隆体自引用地址: {:p}", cloned.get_ref().unwrap());
+    assert!(!std::ptr::eq(cloned.get_ref().unwrap(), original.get_ref().unwrap()));
+    assert!(cloned.with_data_and_ref(|data, view| view.is_some_and(|v| std::ptr::eq(data, v))));
+    drop(original);
+    println!("原实例释放后，克隆体自引用指向：{}", cloned.get_ref().unwrap());
+    println!("PlainBox 克隆：{}", plain.clone());
+    println!("克隆体与新建实例相等：{}",
+        cloned == OptionalSelfRef::new_with_ref(String::from("被克隆的数据")));
+
+    // ========== 场景6：按数据放入 HashSet / BTreeSet ==========
+    println!("\n=== 按数据比较与哈希 ===");
+    let pinned_a = OptionalSelfRef::new_with_ref(String::from("a"));
+    let pinned_b = OptionalSelfRef::new_with_ref(String::from("b"));
+    let mut set = HashSet::new();
+    set.insert(&*pinned_a);
+    set.insert(&*pinned_b);
+    let lookup = OptionalSelfRef::new_pinned(String::from("a"));
+    println!("以无自引用的实例查找 \"a\"：{}", set.contains(&*lookup));
+    assert!(set.contains(&*lookup));
+    println!("PlainBox 与固定实例相等：{}", OptionalSelfRef::new_no_ref(String::from("b")) == *pinned_b);
+
+    let ordered: BTreeSet<_> = [3, 1, 2].into_iter().map(OptionalSelfRef::new_no_ref).collect();
+    println!("BTreeSet 按数据排序：{:?}", ordered.iter().map(|v| v.to_string()).collect::<Vec<_>>());
+
+    // ========== 场景7：把固定的自引用实例交给其他线程 ==========
+    println!("\n=== 跨线程（Send）===");
+    let shared = OptionalSelfRef::new_with_ref(String::from("跨线程的自引用"));
+    let handle = thread::spawn(move || {
+        let read = shared.get_ref().unwrap().clone();
+        (shared, read)
+    });
+    let (shared, read) = handle.join().unwrap();
+    println!("子线程读取到：{}，回到主线程后自引用有效：{}", read, shared.is_ref_valid());
+    assert_eq!(read, "跨线程的自引用");
+    assert!(shared.is_ref_valid());
+
+    // ========== 场景8：变换数据，自引用指向新数据 ==========
+    println!("\n=== 变换数据（map_data）===");
+    let words = OptionalSelfRef::new_with_ref(String::from("四个汉字"));
+    let old_addr = words.get_ref().unwrap() as *const String as *const u8;
+    let char_count = words.map_data(|s| s.chars().count());
+    println!("变换后：{}", char_count);
+    println!("新自引用地址: {:p}，旧数据地址: {:p}", char_count.get_ref().unwrap(), old_addr);
+    assert_eq!(char_count.get_ref(), Some(&4));
+    // 新自引用指向新实例自己的数据
+    assert!(char_count.with_data_and_ref(|data, view| view.is_some_and(|v| std::ptr::eq(data, v))));
+    println!("PlainBox 变换：{}", OptionalSelfRef::new_no_ref(21).map_data(|n| n * 2));
+    assert_eq!(OptionalSelfRef::new_no_ref(21).map_data(|n| n * 2), OptionalSelfRef::new_no_ref(42));
+    // 没有自引用的固定实例变换后同样没有自引用
+    let len = OptionalSelfRef::new_pinned(String::from("abc")).map_data(|s| s.len());
+    println!("String → usize：{}，自引用：{:?}", len, len.get_ref());
+    assert_eq!(len.with_data_and_ref(|data, view| (*data, view.is_none())), (3, true));
+
+    // ========== 场景9：不定长数据（str / [u8]）==========
+    println!("\n=== 不定长数据 ===");
+    let text: Box<str> = "不定长的字符串".into();
+    let text_addr = text.as_ptr();
+    let pinned_str = OptionalSelfRef::from_boxed_with_ref(text);
+    println!("OptionalSelfRef<str> 自引用指向：{}", pinned_str.get_ref().unwrap());
+    println!("自引用地址: {:p}，Box 数据地址: {:p}", pinned_str.get_ref().unwrap().as_ptr(), text_addr);
+    let bytes: Box<[u8]> = vec![1, 2, 3].into_boxed_slice();
+    let pinned_bytes = OptionalSelfRef::from_boxed_with_ref(bytes);
+    println!("OptionalSelfRef<[u8]> 自引用指向：{:?}，长度：{}",
+        pinned_bytes.get_ref().unwrap(), pinned_bytes.get_ref().unwrap().len());
+    let mut plain_str: PlainBox<str> = OptionalSelfRef::from_boxed("movable".into());
+    plain_str.get_data_mut().make_ascii_uppercase();
+    println!("PlainBox<str>：{}", plain_str);
+
+    // ========== 场景10：检查自引用是否仍指向自身数据 ==========
+    println!("\n=== 自引用校验 ===");
+    let mut checked = OptionalSelfRef::new_with_ref(String::from("校验"));
+    checked.as_mut().get_mut_data().push_str(&"，追加".repeat(64));
+    println!("追加后自引用有效：{}", checked.is_ref_valid());
+    println!("无自引用的实例视为有效：{}", OptionalSelfRef::new_no_ref(0).is_ref_valid());
+
+    // ========== 场景11：取回数据 ==========
+    println!("\n=== 取回数据（take_data / into_data）===");
+    let taken = OptionalSelfRef::new_no_ref(String::from("可移动的数据")).take_data();
+    println!("无自引用时 take_data：{:?}", taken);
+    let unpinned = OptionalSelfRef::into_unpinned(OptionalSelfRef::new_pinned(3)).unwrap();
+    println!("解除固定后 take_data：{:?}", unpinned.take_data());
+    let original = String::from("固定实例的数据");
+    // ❌ 有自引用时无法拿到按值的实例，也就无法 take_data
+    let pinned_data = OptionalSelfRef::into_unpinned(OptionalSelfRef::new_with_ref(original.clone()))
+        .unwrap_err();
+    println!("有自引用时拒绝解除固定，自引用仍指向：{}", pinned_data.get_ref().unwrap());
+    let extracted = OptionalSelfRef::into_data(pinned_data);
+    println!("into_data 取出：{}，与原值相等：{}", extracted, extracted == original);
+    assert_eq!(extracted, original);
+
+    // ========== 场景12：原地替换固定实例的数据 ==========
+    println!("\n=== 替换数据（replace_data）===");
+    let mut replaced = OptionalSelfRef::new_with_ref(String::from("旧数据"));
+    let before = replaced.get_ref().unwrap() as *const String;
+    let old = replaced.as_mut().replace_data(String::from("新数据"));
+    let after = replaced.get_ref().unwrap() as *const String;
+    println!("返回旧值：{}，自引用指向：{}", old, replaced.get_ref().unwrap());
+    println!("替换前地址: {:p}，替换后地址: {:p}，相同：{}", before, after, before == after);
+    assert_eq!((old.as_str(), before), ("旧数据", after));
+
+    // ========== 场景13：闭包作用域内访问自引用 ==========
+    println!("\n=== 闭包访问（with_ref / with_data_and_ref）===");
+    let scoped = OptionalSelfRef::new_with_ref(String::from("作用域借用"));
+    println!("with_ref 计算长度：{:?}", scoped.with_ref(|s| s.len()));
+    println!("无自引用时 with_ref：{:?}", OptionalSelfRef::new_pinned(1).with_ref(|n| n + 1));
+    let same = scoped.with_data_and_ref(|data, view| view.is_some_and(|v| std::ptr::eq(data, v)));
+    println!("with_data_and_ref：自引用指向数据本身：{}", same);
+
+    // ❌ 自引用被破坏后，debug 构建下每次访问都会触发断言
+    #[cfg(all(debug_assertions, feature = "test-utils"))]
+    {
+        let mut corrupted = OptionalSelfRef::new_with_ref(0u32);
+        unsafe { corrupted.as_mut().debug_corrupt_self_ref() };
+        // 临时换成静默的 panic hook，避免打印断言信息
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| corrupted.with_ref(|n| *n)));
+        std::panic::set_hook(hook);
+        println!("破坏自引用后访问触发断言：{}", result.is_err());
+    }
+
+    // ✅ for_each_ref：有自引用才调用，结果累加到捕获的变量
+    let mut total = 0;
+    for item in [OptionalSelfRef::new_with_ref(10), OptionalSelfRef::new_pinned(100), OptionalSelfRef::new_with_ref(5)] {
+        item.for_each_ref(|n| total += n);
+    }
+    println!("for_each_ref 累加（跳过无自引用的实例）：{}", total);
+    assert_eq!(total, 15);
+
+    // ========== 场景14：闭包内修改数据，自引用自动同步 ==========
+    println!("\n=== 闭包修改（with_mut）===");
+    let mut edited = OptionalSelfRef::new_with_ref(vec![1, 2]);
+    let new_len = edited.as_mut().with_mut(|v| {
+        v.extend(3..=100);
+        v.len()
+    });
+    println!("with_mut 返回：{}，自引用有效：{}，末尾元素：{:?}",
+        new_len, edited.is_ref_valid(), edited.get_ref().unwrap().last());
+
+    // ========== 场景15：嵌套的自引用容器，通过固定视图逐层读取 ==========
+    println!("\n=== 固定视图（get_ref_pinned）===");
+    let inner = OptionalSelfRef::new_no_ref(7).into_with_ref();
+    let mut outer = OptionalSelfRef::new_with_ref(inner);
+    // 外层固定后，内层以 Pin<&mut _> 交出，可以在原地建立内层的自引用
+    outer.as_mut().get_data_mut().init_self_ref();
+    let inner_pinned: Pin<&OptionalSelfRef<i32, WithRef>> = outer.as_ref().get_ref_pinned().unwrap();
+    let value: Pin<&i32> = inner_pinned.get_ref_pinned().unwrap();
+    println!("外层 → 内层 → 数据：{}", *value);
+
+    // ========== 场景16：Default 构造 ==========
+    println!("\n=== 默认值（Default / default_pinned）===");
+    let plain_default = make_plain::<String>();
+    let pinned_default = make_pinned::<Vec<u8>>();
+    println!("Default：{:?}", plain_default);
+    println!("default_pinned：{:?}", pinned_default);
+    println!("default_pinned 自引用指向：{:?}", pinned_default.get_ref());
+    println!("Display：{} / {}", make_plain::<i32>(), make_pinned::<i32>());
+
+    // ========== 场景17：遍历固定的 Vec ==========
+    println!("\n=== 遍历（iter / len）===");
+    let numbers = OptionalSelfRef::new_with_ref(vec![1, 2, 3]);
+    let collected: Vec<i32> = numbers.iter().copied().collect();
+    assert_eq!(collected, [1, 2, 3]);
+    println!("收集回：{:?}，len = {}", collected, numbers.len());
+    let slice = OptionalSelfRef::from_boxed_with_ref(Box::<[u8]>::from(&b"ab"[..]));
+    println!("[u8] 同样可以遍历：{:?}", slice.iter().collect::<Vec<_>>());
+
+    // ========== 场景18：地址报告（{:#}）==========
+    println!("\n=== 地址报告 ===");
+    let reported = OptionalSelfRef::new_with_ref(String::from("报告"));
+    println!("{:#}", reported);
+    println!("{:#}", OptionalSelfRef::new_no_ref(1));
+    let text = format!("{:#}", reported);
+    assert_eq!(report_field(&text, "数据地址"), report_field(&text, "自引用目标"));
+
+    // ========== 场景19：修改代数与自引用凭证 ==========
+    println!("\n=== 修改代数（RefToken）===");
+    let mut tracked = OptionalSelfRef::new_with_ref(String::from("第一版"));
+    let token = tracked.get_ref_token().unwrap();
+    println!("代数 {}，修改前兑换：{:?}", tracked.generation(), tracked.redeem(token));
+    tracked.as_mut().replace_data(String::from("第二版"));
+    println!("代数 {}，replace_data 后兑换旧凭证：{:?}", tracked.generation(), tracked.redeem(token));
+    let fresh = tracked.get_ref_token().unwrap();
+    println!("换新凭证后兑换：{:?}", tracked.redeem(fresh));
+    let other = OptionalSelfRef::new_with_ref(String::from("第二版"));
+    println!("其他实例的凭证：{:?}", tracked.redeem(other.get_ref_token().unwrap()));
+    assert_eq!(tracked.redeem(token), None);
+    assert_eq!(tracked.redeem(fresh).map(String::as_str), Some("第二版"));
+
+    // ========== 场景20：接管已有的 Box ==========
+    println!("\n=== 接管 Box（from_boxed_with_ref）===");
+    let owned = Box::new([0u8; 16]);
+    let observed = &*owned as *const [u8; 16];
+    let adopted = OptionalSelfRef::from_boxed_with_ref(owned);
+    let target = adopted.get_ref().unwrap() as *const [u8; 16];
+    assert_eq!(observed, target);
+    println!("调用方看到的地址: {:p}，自引用地址: {:p}", observed, target);
+
+    // ========== 场景21：标准转换（From / Into）==========
+    println!("\n=== 标准转换 ===");
+    let from_value: PlainBox<String> = wrap(String::from("From<T>"));
+    let from_box: PlainBox<str> = wrap(Box::<str>::from("From<Box<T>>"));
+    println!("{} / {}", from_value, from_box);
+    let boxed: Box<str> = from_box.into_boxed();
+    println!("into_boxed：{}", boxed);
+    println!("take_data：{:?}", wrap::<i32, _>(5).take_data());
+
+    // ========== 场景22：交换数据，自引用指向新值 ==========
+    println!("\n=== 交换数据（swap_data）===");
+    let mut swapped = OptionalSelfRef::new_with_ref(vec![1, 2, 3]);
+    let old = swapped.as_mut().swap_data(vec![4, 5]);
+    assert_eq!(swapped.get_ref().unwrap(), &[4, 5]);
+    println!("换出的旧值：{:?}，自引用指向：{:?}，有效：{}", old, swapped.get_ref().unwrap(), swapped.is_ref_valid());
+
+    // ========== 场景23：安全取出数据（try_into_unpinned）==========
+    println!("\n=== 安全取出数据 ===");
+    let still_pinned = OptionalSelfRef::try_into_unpinned(OptionalSelfRef::new_with_ref(7))
+        .expect_err("仍有自引用，应原样交还");
+    println!("有自引用：原样交还，自引用仍有效：{}", still_pinned.is_ref_valid());
+    assert_eq!(still_pinned.get_ref(), Some(&7));
+    let mut cleared = still_pinned;
+    cleared.as_mut().clear_self_ref();
+    let value = OptionalSelfRef::try_into_unpinned(cleared).expect("自引用已清除");
+    println!("清除自引用后：取出 {}", value);
+    assert_eq!(value, 7);
+
+    // ========== 场景24：Debug 输出地址与不变量状态 ==========
+    println!("\n=== Debug ===");
+    let healthy = OptionalSelfRef::new_with_ref(42);
+    println!("{:?}", healthy);
+    assert!(format!("{:?}", healthy).contains("invariant_ok: true"));
+    println!("{:?}", OptionalSelfRef::new_no_ref(1));
+    #[cfg(all(debug_assertions, feature = "test-utils"))]
+    {
+        let mut dangling = OptionalSelfRef::new_with_ref(42);
+        unsafe { dangling.as_mut().debug_corrupt_self_ref() };
+        println!("{:?}", dangling);
+        assert!(format!("{:?}", dangling).contains("invariant_ok: false"));
+
+        // verify_invariant 给出期望地址（数据）与实际地址（悬垂指针）
+        let violation = dangling.verify_invariant().unwrap_err();
+        println!("verify_invariant：{}", violation);
+        let dangling_addr = std::ptr::NonNull::<i32>::dangling().as_ptr() as usize;
+        assert!(matches!(violation,
+            InvariantViolation::SelfRefMismatch { expected, actual } if actual == dangling_addr && expected != actual));
+    }
+    assert_eq!(healthy.verify_invariant(), Ok(()));
+
+    // ========== 场景25：自引用的字节偏移 ==========
+    println!("\n=== ref_offset ===");
+    println!("new_with_ref：{:?}，无自引用：{:?}", healthy.ref_offset(), OptionalSelfRef::new_no_ref(1).ref_offset());
+    assert_eq!(healthy.ref_offset(), Some(0));
+    assert_eq!(OptionalSelfRef::new_no_ref(1).ref_offset(), None);
+
+    // ========== 场景26：指针来源（预期在 cargo miri run 下通过）==========
+    // 固定 → 经自引用读取 → 修改其他字段 → 再次读取，任何一步都不应使 self_ref 失效；
+    // CI 的 nightly 任务以 -Zmiri-strict-provenance 与 Tree Borrows 分别运行本示例
+    println!("\n=== 指针来源 ===");
+    let mut tracked = OptionalSelfRef::new_with_ref(String::from("来源"));
+    // 只修改修改代数：交出可变访问但不写入数据
+    let _ = tracked.as_mut().get_data_mut();
+    assert_eq!(tracked.generation(), 1);
+    // 重写 self_ref 字段本身
+    tracked.as_mut().clear_self_ref();
+    assert!(tracked.as_mut().init_self_ref());
+    // 经由 as_pin_mut 推导的访问修改数据后，旧的 self_ref 来源仍然有效
+    tracked.as_mut().with_mut(|s| s.push('！'));
+    println!("再次读取：{}，修改代数：{}", tracked.get_ref().unwrap(), tracked.generation());
+    assert_eq!(tracked.get_ref().map(String::as_str), Some("来源！"));
+    assert_eq!(tracked.ref_offset(), Some(0));
+
+    // ========== 场景27：交换两个固定实例的数据（swap）==========
+    println!("\n=== 交换固定实例 ===");
+    let mut left = OptionalSelfRef::new_with_ref(String::from("左"));
+    let mut right = OptionalSelfRef::new_with_ref(String::from("右"));
+    let (left_addr, right_addr) = (&*left as *const _, &*right as *const _);
+    OptionalSelfRef::swap(left.as_mut(), right.as_mut());
+    println!("交换后：{} / {}", left.get_ref().unwrap(), right.get_ref().unwrap());
+    // 内容互换，容器地址不变
+    assert_eq!(left.get_ref().map(String::as_str), Some("右"));
+    assert_eq!(right.get_ref().map(String::as_str), Some("左"));
+    assert!(std::ptr::eq(left_addr, &*left) && std::ptr::eq(right_addr, &*right));
+    // 各自的自引用指向自己的新数据
+    assert!(left.is_ref_valid() && right.is_ref_valid());
+    assert_eq!((left.ref_offset(), right.ref_offset()), (Some(0), Some(0)));
+
+    // ========== 场景28：校验后重新绑定自引用（rebind_ref）==========
+    println!("\n=== rebind_ref ===");
+    let mut bound = OptionalSelfRef::new_with_ref(String::from("绑定目标"));
+    // 模拟经由 FFI 传回的地址：先取出裸指针，之后才需要 Pin<&mut Self>
+    let raw = bound.get_ref().unwrap() as *const String;
+    bound.as_mut().clear_self_ref();
+    // SAFETY：raw 指向 bound 仍然拥有的数据
+    let accepted = bound.as_mut().rebind_ref(unsafe { &*raw });
+    println!("指向自身数据：{:?}，读取：{:?}", accepted, bound.get_ref());
+    assert_eq!(accepted, Ok(()));
+    assert!(bound.is_ref_valid());
+
+    // 栈上的局部变量与其他实例的数据都会被拒绝，原有的自引用保持不变
+    let local = String::from("栈上的局部变量");
+    let rejected = bound.as_mut().rebind_ref(&local);
+    println!("指向局部变量：{}", rejected.as_ref().unwrap_err());
+    assert!(matches!(rejected, Err(RebindError::OutsideData { .. })));
+    let other = OptionalSelfRef::new_with_ref(String::from("其他实例"));
+    assert!(bound.as_mut().rebind_ref(other.get_ref().unwrap()).is_err());
+    assert_eq!(bound.get_ref().map(String::as_str), Some("绑定目标"));
+
+    // 不定长数据：数据内部的子切片不是数据本身
+    let mut bytes = OptionalSelfRef::from_boxed_with_ref(Box::<[u8]>::from(&b"abcdef"[..]));
+    let whole = bytes.get_ref().unwrap() as *const [u8];
+    // SAFETY：whole 指向 bytes 仍然拥有的数据，只截取其中一段
+    let partial = bytes.as_mut().rebind_ref(unsafe { &(&*whole)[1..3] });
+    println!("指向子切片：{}", partial.as_ref().unwrap_err());
+    assert_eq!(partial, Err(RebindError::NotWholeData { offset: 1, len: 2 }));
+
+    // ========== 场景29：组合两份数据（zip）==========
+    println!("\n=== zip ===");
+    let zipped = OptionalSelfRef::zip(42, String::from("答案"));
+    println!("第一个：{:?}，第二个：{:?}", zipped.ref_to_first(), zipped.ref_to_second());
+    assert_eq!(zipped.ref_to_first(), Some(&42));
+    assert_eq!(zipped.ref_to_second().map(String::as_str), Some("答案"));
+    // 元素地址都落在自引用指向的元组之内
+    let tuple = zipped.get_ref().unwrap() as *const (i32, String) as usize;
+    let size = std::mem::size_of::<(i32, String)>();
+    for addr in [
+        zipped.ref_to_first().unwrap() as *const i32 as usize,
+        zipped.ref_to_second().unwrap() as *const String as usize,
+    ] {
+        assert!((tuple..tuple + size).contains(&addr));
+    }
+    assert_eq!(OptionalSelfRef::new_no_ref((1, 2)).ref_to_first(), None);
+
+    // ========== 场景30：裸指针往返（into_raw / from_raw）==========
+    println!("\n=== 裸指针往返 ===");
+    let pinned = OptionalSelfRef::new_with_ref(String::from("跨 FFI 边界"));
+    let before = &*pinned as *const OptionalSelfRef<String, WithRef>;
+    let raw = OptionalSelfRef::into_raw(pinned);
+    println!("交出的裸指针：{:p}", raw);
+    assert_eq!(raw as *const _, before);
+    // SAFETY：raw 来自上面的 into_raw，只取回这一次
+    let restored = unsafe { OptionalSelfRef::from_raw(raw) };
+    println!("取回后自引用指向：{:?}", restored.get_ref());
+    assert_eq!(restored.get_ref().map(String::as_str), Some("跨 FFI 边界"));
+    assert!(restored.is_ref_valid());
+
+    // ========== 场景31：存活计数（只在 debug 构建中计数，release 构建始终为 0）==========
+    println!("\n=== 存活计数 ===");
+    let baseline = live_self_ref_count();
+    println!("当前存活的自引用实例：{}", baseline);
+    let batch: Vec<_> = (0..100).map(OptionalSelfRef::new_with_ref).collect();
+    println!("新建 100 个后：{}", live_self_ref_count());
+    let expected = if cfg!(debug_assertions) { baseline + 100 } else { 0 };
+    assert_eq!(live_self_ref_count(), expected);
+    drop(batch);
+    assert_eq!(live_self_ref_count(), baseline);
+    println!("全部释放后回到：{}", live_self_ref_count());
+}
+
+// 从多行报告中取出「标签: 值」的值
+fn report_field<'a>(report: &'a str, label: &str) -> &'a str {
+    report
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(label)?.strip_prefix(": "))
+        .expect("报告中缺少该字段")
+}
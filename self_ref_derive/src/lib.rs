@@ -0,0 +1,211 @@
+//! `#[derive(SelfReferential)]` 与 `#[self_referential]`：通过 `rust_advanced` 的 `derive` feature 使用
+//!
+//! 生成的代码经由 `rust_advanced::__Box` 引用 Box，`#![no_std]` 下同样可用；
+//! crate 路径由 `proc-macro-crate` 从调用方的 Cargo.toml 解析（依赖被重命名时同样可用），
+//! 也可以用结构体上的 `#[self_ref(crate = path)]` 显式指定
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use proc_macro_crate::{crate_name, FoundCrate};
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Field, Fields, FieldsNamed, Path, Type};
+
+/// 为带 `#[payload]` 数据字段与 `#[self_ref]` 裸指针字段的结构体生成：
+/// - `new(payload) -> Pin<Box<Self>>`：先固定，再让自引用指向固定位置上的数据字段；
+/// - `get_ref(&self) -> &Payload`：经由自引用读取数据。
+///
+/// 其余字段以 `Default::default()` 初始化；derive 宏无法添加字段，结构体必须自带 `PhantomPinned` 字段，
+/// 需要自动插入时改用 `#[self_referential]`
+#[proc_macro_derive(SelfReferential, attributes(payload, self_ref))]
+pub fn derive_self_referential(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// 与 `#[derive(SelfReferential)]` 生成相同的 `new` / `get_ref`，
+/// 结构体没有 `PhantomPinned` 字段时自动插入 `_pin: PhantomPinned`
+#[proc_macro_attribute]
+pub fn self_referential(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(item as DeriveInput);
+    if !args.is_empty() {
+        let args = proc_macro2::TokenStream::from(args);
+        return Error::new_spanned(args, "#[self_referential] 不接受参数，crate 路径用 #[self_ref(crate = path)] 指定")
+            .into_compile_error()
+            .into();
+    }
+    attribute(&mut input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn derive(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = named_fields(input)?;
+    // derive 宏无法向结构体添加字段，缺少 PhantomPinned 时给出编译错误
+    if !fields.named.iter().any(|field| is_phantom_pinned(&field.ty)) {
+        return Err(Error::new(
+            Span::call_site(),
+            "SelfReferential 要求一个 PhantomPinned 字段（如 `_pin: PhantomPinned`），否则类型仍是 Unpin；\
+             改用 #[self_referential] 可自动插入",
+        ));
+    }
+    expand(input)
+}
+
+fn attribute(input: &mut DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    // 缺少 PhantomPinned 时插入一个，new 中与其他字段一样以 Default 初始化
+    let fields = named_fields_mut(input)?;
+    if !fields.named.iter().any(|field| is_phantom_pinned(&field.ty)) {
+        let name = format_ident!("_pin");
+        if let Some(taken) = fields.named.iter().find(|field| field.ident.as_ref() == Some(&name)) {
+            return Err(Error::new_spanned(taken, "无法插入 `_pin: PhantomPinned`：已有同名字段"));
+        }
+        fields.named.push(Field::parse_named.parse2(quote!(#name: ::core::marker::PhantomPinned))?);
+    }
+    let generated = expand(input)?;
+
+    // 属性宏展开后没有 derive 的辅助属性，标记属性必须移除
+    input.attrs.retain(|attr| !attr.path().is_ident("self_ref"));
+    for field in &mut named_fields_mut(input)?.named {
+        field.attrs.retain(|attr| !is_marker(attr));
+    }
+    Ok(quote! {
+        #input
+        #generated
+    })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = &named_fields(input)?.named;
+    let krate = crate_path(&input.attrs)?;
+
+    let payload = find_marked(fields.iter(), "payload")?
+        .ok_or_else(|| Error::new_spanned(name, "缺少 #[payload] 字段：自引用要指向的数据"))?;
+    let self_ref = find_marked(fields.iter(), "self_ref")?
+        .ok_or_else(|| Error::new_spanned(name, "缺少 #[self_ref] 字段：指向 #[payload] 的裸指针"))?;
+
+    // 自引用字段必须是指向数据类型的裸指针
+    let payload_ty = &payload.ty;
+    match &self_ref.ty {
+        Type::Ptr(ptr) if same_type(&ptr.elem, payload_ty) => {}
+        Type::Ptr(ptr) => {
+            return Err(Error::new_spanned(&ptr.elem, "#[self_ref] 裸指针的目标类型必须与 #[payload] 字段相同"));
+        }
+        other => return Err(Error::new_spanned(other, "#[self_ref] 字段必须是裸指针（*const T 或 *mut T）")),
+    }
+
+    let payload_ident = payload.ident.as_ref().expect("具名字段");
+    let self_ref_ident = self_ref.ident.as_ref().expect("具名字段");
+    let others = fields
+        .iter()
+        .filter(|field| field.ident != payload.ident && field.ident != self_ref.ident)
+        .map(|field| field.ident.as_ref().expect("具名字段"));
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// 创建固定在堆上的实例，固定之后才建立自引用
+            #[allow(dead_code)]
+            #vis fn new(#payload_ident: #payload_ty) -> ::core::pin::Pin<#krate::__Box<Self>> {
+                let mut pinned = #krate::__Box::pin(#name {
+                    #payload_ident,
+                    #self_ref_ident: ::core::ptr::null_mut(),
+                    #(#others: ::core::default::Default::default(),)*
+                });
+                // SAFETY：只写入自引用字段，不移动实例
+                let this = unsafe { pinned.as_mut().get_unchecked_mut() };
+                this.#self_ref_ident = ::core::ptr::addr_of_mut!(this.#payload_ident);
+                pinned
+            }
+
+            /// 通过自引用读取数据
+            ///
+            /// # Panics
+            /// 自引用没有指向自身的数据字段时 panic（例如未经 `new` 手动构造）
+            #[allow(dead_code)]
+            #vis fn get_ref(&self) -> &#payload_ty {
+                let ptr = self.#self_ref_ident as *const #payload_ty;
+                assert!(
+                    ::core::ptr::eq(ptr, &self.#payload_ident),
+                    "自引用没有指向自身的 #[payload] 字段"
+                );
+                // SAFETY：ptr 与 &self 中的数据字段地址相同，借用期间有效
+                unsafe { &*ptr }
+            }
+        }
+    })
+}
+
+fn named_fields(input: &DeriveInput) -> syn::Result<&FieldsNamed> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields),
+            _ => Err(Error::new_spanned(&input.ident, "SelfReferential 只支持具名字段的结构体")),
+        },
+        _ => Err(Error::new_spanned(&input.ident, "SelfReferential 只支持结构体")),
+    }
+}
+
+fn named_fields_mut(input: &mut DeriveInput) -> syn::Result<&mut FieldsNamed> {
+    let ident = input.ident.clone();
+    match &mut input.data {
+        Data::Struct(data) => match &mut data.fields {
+            Fields::Named(fields) => Ok(fields),
+            _ => Err(Error::new_spanned(ident, "SelfReferential 只支持具名字段的结构体")),
+        },
+        _ => Err(Error::new_spanned(ident, "SelfReferential 只支持结构体")),
+    }
+}
+
+// rust_advanced 的路径：结构体上的 #[self_ref(crate = path)] 优先，否则按调用方 Cargo.toml 中的依赖名解析
+fn crate_path(attrs: &[Attribute]) -> syn::Result<proc_macro2::TokenStream> {
+    let mut explicit = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("self_ref")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                explicit = Some(meta.value()?.parse::<Path>()?);
+                Ok(())
+            } else {
+                Err(meta.error("未知的参数，只支持 #[self_ref(crate = path)]"))
+            }
+        })?;
+    }
+    if let Some(path) = explicit {
+        return Ok(quote!(#path));
+    }
+    // 宏只在使用 rust_advanced 的 crate 中展开，Itself 只会出现在它自己的示例与测试中，同样走 extern 路径
+    match crate_name("rust_advanced") {
+        Ok(FoundCrate::Name(name)) => {
+            let ident = format_ident!("{}", name);
+            Ok(quote!(::#ident))
+        }
+        Ok(FoundCrate::Itself) | Err(_) => Ok(quote!(::rust_advanced)),
+    }
+}
+
+// 找到带指定标记属性的唯一字段；标记重复时报错
+fn find_marked<'a>(fields: impl Iterator<Item = &'a Field>, marker: &str) -> syn::Result<Option<&'a Field>> {
+    let mut found = None;
+    for field in fields {
+        if field.attrs.iter().any(|attr| attr.path().is_ident(marker)) {
+            if found.is_some() {
+                return Err(Error::new_spanned(field, format!("只能有一个 #[{}] 字段", marker)));
+            }
+            found = Some(field);
+        }
+    }
+    Ok(found)
+}
+
+fn is_marker(attr: &Attribute) -> bool {
+    attr.path().is_ident("payload") || attr.path().is_ident("self_ref")
+}
+
+// 按记号比较类型，足以识别 `*const T` 与 `T` 写法一致的情况
+fn same_type(a: &Type, b: &Type) -> bool {
+    quote!(#a).to_string() == quote!(#b).to_string()
+}
+
+fn is_phantom_pinned(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.segments.last().is_some_and(|seg| seg.ident == "PhantomPinned"))
+}
@@ -0,0 +1,63 @@
+//! rust高级知识训练
+//!
+//! 默认启用 `std` feature；关闭默认 feature 并启用 `alloc` 时以 `#![no_std]` 编译，
+//! 只依赖 `alloc` 中的 `Box` / `String` / `Vec` / `Arc`
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod pin;
+
+// 供导出的宏在 no_std 下引用 Box，调用方无需自行声明 `extern crate alloc`
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub use alloc::boxed::Box as __Box;
+
+/// `#[derive(SelfReferential)]` / `#[self_referential]`：为带 `#[payload]` 与 `#[self_ref]` 字段的结构体生成 `new` / `get_ref`
+///
+/// `#[self_ref]` 必须是指向 `#[payload]` 类型的裸指针，其余字段以 `Default::default()` 初始化。
+/// derive 宏无法添加字段，结构体需自行声明 `PhantomPinned` 字段：
+///
+/// ```
+/// use core::marker::PhantomPinned;
+/// use rust_advanced::SelfReferential;
+///
+/// #[derive(SelfReferential)]
+/// struct Doc {
+///     #[payload]
+///     text: String,
+///     #[self_ref]
+///     ptr: *const String,
+///     _pin: PhantomPinned,
+/// }
+///
+/// let doc = Doc::new(String::from("派生宏"));
+/// assert_eq!(doc.get_ref(), "派生宏");
+/// assert!(core::ptr::eq(doc.ptr, &doc.text));
+/// ```
+///
+/// 属性宏 `#[self_referential]` 在缺少时自动插入 `_pin: PhantomPinned`；
+/// 依赖被重命名时 crate 路径自动解析，也可以用 `#[self_ref(crate = path)]` 显式指定：
+///
+/// ```
+/// use rust_advanced::self_referential;
+///
+/// #[self_referential]
+/// struct Doc {
+///     #[payload]
+///     text: String,
+///     #[self_ref]
+///     ptr: *const String,
+/// }
+///
+/// let doc = Doc::new(String::from("属性宏"));
+/// assert_eq!(doc.get_ref(), "属性宏");
+/// ```
+///
+/// 编译失败的情形由 `tests/派生宏.rs` 中的 trybuild 用例覆盖
+#[cfg(feature = "derive")]
+pub use self_ref_derive::{self_referential, SelfReferential};
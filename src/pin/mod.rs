@@ -0,0 +1,130 @@
+//! Pin 与自引用结构体
+
+// 修改方法结束时校验自引用不变量（仅 debug 构建），校验失败直接 panic
+macro_rules! debug_verify {
+    ($this:expr) => {
+        #[cfg(debug_assertions)]
+        if let Err(violation) = $this.verify_invariant() {
+            panic!("自引用不变量被破坏：{}", violation);
+        }
+    };
+}
+
+#[path = "固定结构体.rs"]
+mod self_ref;
+#[path = "解除pin固定.rs"]
+mod optional_self_ref;
+#[path = "字段自引用.rs"]
+mod mapped_self_ref;
+#[path = "偏移自引用.rs"]
+mod offset_self_ref;
+#[path = "自引用宏.rs"]
+mod macros;
+#[path = "共享自引用.rs"]
+mod shared_self_ref;
+#[path = "切片自引用.rs"]
+mod slice_self_ref;
+#[path = "字节自引用.rs"]
+mod byte_self_ref;
+#[path = "双端自引用.rs"]
+mod pair_self_ref;
+#[path = "内联自引用.rs"]
+mod inline_self_ref;
+#[path = "异步自引用.rs"]
+mod future_self_ref;
+#[path = "自引用竞技场.rs"]
+mod arena;
+#[cfg(feature = "pin-project")]
+#[path = "投影自引用.rs"]
+mod projected_self_ref;
+#[path = "泛型自引用.rs"]
+mod generic_self_ref;
+#[path = "下标自引用.rs"]
+mod index_self_ref;
+#[path = "惰性自引用.rs"]
+mod once_self_ref;
+#[path = "弱引用.rs"]
+mod pin_weak;
+#[path = "多重自引用.rs"]
+mod multi_self_ref;
+#[cfg(feature = "std")]
+#[path = "映射自引用.rs"]
+mod map_self_ref;
+#[path = "内部可变自引用.rs"]
+mod cell_self_ref;
+#[path = "自引用栈.rs"]
+mod self_ref_stack;
+#[path = "命名自引用.rs"]
+mod named_self_ref;
+#[path = "构建器.rs"]
+mod builder;
+#[path = "错误.rs"]
+mod error;
+
+pub use arena::SelfRefArena;
+pub use byte_self_ref::SelfRefBytes;
+pub use builder::{OptionalSelfRefBuilder, SelfRefBuilder};
+pub use cell_self_ref::{SelfRefCell, SelfRefCellGuard};
+pub use error::{BuildError, InvariantViolation, RangeError, RebindError, SelfRefError};
+pub use future_self_ref::SelfRefFuture;
+pub use generic_self_ref::SelfRefGeneric;
+pub use inline_self_ref::InlineSelfRef;
+pub use mapped_self_ref::MappedSelfRef;
+pub use multi_self_ref::{MultiSelfRef, RefId};
+pub use named_self_ref::NamedSelfRef;
+pub use offset_self_ref::OffsetSelfRef;
+pub use once_self_ref::OnceSelfRef;
+pub use optional_self_ref::{NoRef, OptionalSelfRef, PlainBox, RefToken, WithRef};
+pub use optional_self_ref::live_self_ref_count;
+pub use pair_self_ref::SelfRefPair;
+pub use pin_weak::PinWeak;
+pub use self_ref::{AppendOutcome, SelfRef, SelfRefView};
+pub use self_ref_stack::SelfRefStack;
+pub use shared_self_ref::SharedSelfRef;
+pub use slice_self_ref::SelfRefSlice;
+pub use index_self_ref::VecSelfRef;
+
+#[cfg(feature = "serde")]
+pub use optional_self_ref::DeserializedSelfRef;
+#[cfg(feature = "serde")]
+pub use self_ref::SelfRefDef;
+#[cfg(feature = "std")]
+pub use map_self_ref::SelfRefMap;
+#[cfg(feature = "pin-project")]
+pub use projected_self_ref::{ProjectedSelfRef, SelfRefProjection};
+
+// 只有 alloc 时的核心路径：new_with_ref / get_ref / update_data，
+// 运行方式：`cargo test --no-default-features --features alloc --lib`
+#[cfg(all(test, not(feature = "std")))]
+mod no_std_tests {
+    use super::{OptionalSelfRef, SelfRef};
+    use alloc::{string::String, vec};
+
+    #[test]
+    fn optional_self_ref_points_at_own_data() {
+        let mut pinned = OptionalSelfRef::new_with_ref(String::from("no_std"));
+        assert_eq!(pinned.get_ref().map(String::as_str), Some("no_std"));
+        assert!(pinned.is_ref_valid());
+        pinned.as_mut().get_mut_data().push_str(" + alloc");
+        assert_eq!(pinned.get_ref().map(String::as_str), Some("no_std + alloc"));
+        assert!(pinned.is_ref_valid());
+    }
+
+    #[test]
+    fn optional_self_ref_no_ref_moves_freely() {
+        let no_ref = OptionalSelfRef::new_no_ref(vec![1, 2, 3]);
+        let moved = no_ref;
+        assert_eq!(moved.get_ref(), None);
+        assert_eq!(moved.take_data().ok(), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn self_ref_update_data_resyncs_ptr() {
+        let mut pinned = SelfRef::new("嵌入式");
+        assert_eq!(pinned.get_ref(), "嵌入式");
+        pinned.as_mut().update_data("更长的内容，缓冲区重新分配");
+        assert_eq!(pinned.get_ref(), "更长的内容，缓冲区重新分配");
+        assert_eq!(pinned.get_ref().as_ptr(), pinned.data().as_ptr());
+        assert!(pinned.validate());
+    }
+}
@@ -0,0 +1,109 @@
+use alloc::vec::Vec;
+
+/// 以下标代替指针指向 Vec 中的「当前元素」
+///
+/// Vec 扩容会把元素搬到新的缓冲区，指向元素的裸指针随即悬垂；下标只记录逻辑位置，
+/// 每次访问都从当前缓冲区重新取元素，因此扩容、移动容器都不影响，也无需 Pin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VecSelfRef<T> {
+    items: Vec<T>,
+    // 当前元素的下标，None 表示未选中
+    current: Option<usize>,
+}
+
+impl<T> VecSelfRef<T> {
+    /// 以已有的 Vec 创建，初始未选中任何元素
+    pub fn new(items: Vec<T>) -> Self {
+        VecSelfRef {
+            items,
+            current: None,
+        }
+    }
+
+    /// 选中 `idx` 处的元素；越界时返回 `None` 且保持原有选择
+    pub fn select(&mut self, idx: usize) -> Option<&T> {
+        let item = self.items.get(idx)?;
+        self.current = Some(idx);
+        Some(item)
+    }
+
+    /// 读取当前元素
+    pub fn current(&self) -> Option<&T> {
+        self.items.get(self.current?)
+    }
+
+    /// 当前元素此刻的地址，仅用于演示：扩容后地址会变化，而 `current` 始终正确
+    pub fn current_ptr(&self) -> Option<*const T> {
+        self.current().map(|item| item as *const T)
+    }
+
+    /// 追加元素，选择保持不变（即使发生扩容）
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    /// 移除 `idx` 处的元素：移除的正是当前元素时清除选择，移除在它之前时下标前移一位
+    pub fn remove(&mut self, idx: usize) -> Option<T> {
+        if idx >= self.items.len() {
+            return None;
+        }
+        self.current = match self.current {
+            Some(current) if current == idx => None,
+            Some(current) if current > idx => Some(current - 1),
+            other => other,
+        };
+        Some(self.items.remove(idx))
+    }
+
+    /// 读取全部元素
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// 元素个数
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// 是否没有元素
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::VecSelfRef;
+
+    #[test]
+    fn selection_survives_reallocation() {
+        let mut list = VecSelfRef::new(vec![1, 2, 3]);
+        assert_eq!(list.current(), None);
+        assert_eq!(list.select(1), Some(&2));
+        for i in 0..1000 {
+            list.push(i);
+        }
+        // 多次扩容后下标仍指向同一个逻辑元素，且落在当前缓冲区内
+        assert_eq!(list.current(), Some(&2));
+        assert!(core::ptr::eq(list.current().unwrap(), &list.items()[1]));
+    }
+
+    #[test]
+    fn remove_adjusts_selection() {
+        let mut list = VecSelfRef::new(vec!['a', 'b', 'c', 'd']);
+        list.select(2);
+        assert_eq!(list.remove(0), Some('a'));
+        assert_eq!(list.current(), Some(&'c'));
+        assert_eq!(list.remove(2), Some('d'));
+        assert_eq!(list.current(), Some(&'c'));
+        assert_eq!(list.remove(1), Some('c'));
+        assert_eq!(list.current(), None);
+        assert_eq!(list.remove(5), None);
+        // 越界选择保持原状
+        list.select(0);
+        assert_eq!(list.select(9), None);
+        assert_eq!(list.current(), Some(&'b'));
+    }
+}
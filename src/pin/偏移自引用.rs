@@ -0,0 +1,127 @@
+use core::ops::Range;
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// 以「偏移量」代替裸指针的自引用容器
+///
+/// 每次访问都从 data 当前的地址加上偏移量重新计算引用，不保存任何绝对地址，
+/// 因此无需 Pin：实例是 Unpin 的，可以自由移动、放进 Vec、跨函数传递，且不含 unsafe。
+/// 指向整个数据的自引用偏移恒为 0，直接借用 data；真正的偏移只出现在 String / Vec<u8> 的子区间视图中
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetSelfRef<T> {
+    data: Box<T>,
+    // 是否建立了指向整个 data 的自引用
+    has_ref: bool,
+    // String / Vec<u8> 的子区间视图：(起始字节, 长度)，相对缓冲区起始地址
+    range: Option<(usize, usize)>,
+}
+
+impl<T> OffsetSelfRef<T> {
+    /// 创建无自引用的实例
+    pub fn new(data: T) -> Self {
+        OffsetSelfRef {
+            data: Box::new(data),
+            has_ref: false,
+            range: None,
+        }
+    }
+
+    /// 创建自引用指向整个数据的实例（偏移为 0）
+    pub fn new_with_ref(data: T) -> Self {
+        OffsetSelfRef {
+            has_ref: true,
+            ..Self::new(data)
+        }
+    }
+
+    /// 读取自引用：从 &self 现场借用 data，生命周期与 &self 绑定
+    pub fn get_ref(&self) -> Option<&T> {
+        self.has_ref.then_some(&*self.data)
+    }
+
+    /// 读取完整的数据
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+}
+
+impl OffsetSelfRef<String> {
+    /// 让自引用指向 `range` 对应的子串，越界或不在字符边界上时返回 `None` 且不修改原有视图
+    pub fn pin_range(&mut self, range: Range<usize>) -> Option<&str> {
+        self.data.get(range.clone())?;
+        self.range = Some((range.start, range.len()));
+        self.get_range()
+    }
+
+    /// 读取子串视图：由缓冲区当前地址 + 起始偏移重新计算
+    pub fn get_range(&self) -> Option<&str> {
+        // 偏移量相对缓冲区，按当前数据现场切片，不依赖任何旧地址
+        self.range.map(|(start, len)| &self.data[start..start + len])
+    }
+}
+
+impl OffsetSelfRef<Vec<u8>> {
+    /// 让自引用指向 `range` 对应的子切片，越界时返回 `None` 且不修改原有视图
+    pub fn pin_range(&mut self, range: Range<usize>) -> Option<&[u8]> {
+        self.data.get(range.clone())?;
+        self.range = Some((range.start, range.len()));
+        self.get_range()
+    }
+
+    /// 读取子切片视图：由缓冲区当前地址 + 起始偏移重新计算
+    pub fn get_range(&self) -> Option<&[u8]> {
+        self.range.map(|(start, len)| &self.data[start..start + len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use super::OffsetSelfRef;
+
+    fn assert_unpin<T: Unpin>() {}
+
+    // 按值传入、按值返回，迫使实例跨函数边界移动
+    fn pass_through<T>(value: OffsetSelfRef<T>) -> OffsetSelfRef<T> {
+        value
+    }
+
+    #[test]
+    fn offset_self_ref_is_unpin() {
+        assert_unpin::<OffsetSelfRef<String>>();
+        assert_unpin::<OffsetSelfRef<Vec<u8>>>();
+    }
+
+    #[test]
+    fn whole_ref_survives_moves() {
+        let moved = pass_through(OffsetSelfRef::new_with_ref(99));
+        assert_eq!(moved.get_ref(), Some(&99));
+        assert!(core::ptr::eq(moved.get_ref().unwrap(), moved.data()));
+        assert_eq!(OffsetSelfRef::new(1).get_ref(), None);
+    }
+
+    #[test]
+    fn string_range_survives_moves_into_vec() {
+        let mut text = OffsetSelfRef::new(String::from("偏移量天然可移动"));
+        assert_eq!(text.pin_range(3..9), Some("移量"));
+        // 非字符边界：视图保持不变
+        assert_eq!(text.pin_range(0..4), None);
+        let text = pass_through(text);
+        let mut list = vec![text.clone()];
+        list.push(text);
+        for item in &list {
+            assert_eq!(item.get_range(), Some("移量"));
+            assert!(core::ptr::eq(item.get_range().unwrap(), &item.data()[3..9]));
+        }
+    }
+
+    #[test]
+    fn byte_range_survives_moves() {
+        let mut bytes = OffsetSelfRef::new(vec![1u8, 2, 3, 4, 5]);
+        assert_eq!(bytes.pin_range(3..9), None);
+        assert_eq!(bytes.get_range(), None);
+        bytes.pin_range(1..4);
+        let list: Vec<_> = vec![pass_through(bytes)];
+        assert_eq!(list[0].get_range(), Some(&[2u8, 3, 4][..]));
+    }
+}
@@ -0,0 +1,97 @@
+use core::pin::Pin;
+use core::marker::PhantomPinned;
+use core::ptr::NonNull;
+use alloc::{boxed::Box, sync::Arc};
+
+// 共享的固定数据：堆上的 data + 指向它的自引用
+#[derive(Debug)]
+struct Inner<T> {
+    data: Box<T>,
+    self_ref: Option<NonNull<T>>,
+    _pin: PhantomPinned,
+}
+
+// SAFETY：self_ref 只指向同一个 Inner 拥有的 Box<T>，随 Inner 一起转移、一起释放；
+// 共享时只会交出 &T，因此与 Box<T> 的 Send / Sync 条件一致
+unsafe impl<T: Send> Send for Inner<T> {}
+unsafe impl<T: Sync> Sync for Inner<T> {}
+
+/// 基于 `Pin<Arc<_>>` 的共享自引用容器：多个句柄看到同一份固定数据
+///
+/// `Pin<Arc<_>>` 只能得到共享引用，永远不会交出 `&mut`，
+/// 任何句柄都无法移动或替换数据，自引用因此在最后一个句柄释放前始终有效
+#[derive(Debug)]
+pub struct SharedSelfRef<T> {
+    inner: Pin<Arc<Inner<T>>>,
+}
+
+impl<T> SharedSelfRef<T> {
+    /// 创建带自引用的共享实例
+    pub fn new_with_ref(data: T) -> Self {
+        let mut arc = Arc::new(Inner {
+            data: Box::new(data),
+            self_ref: None,
+            _pin: PhantomPinned,
+        });
+        // 共享之前引用计数为 1，可以安全地取得唯一的可变引用来建立自引用
+        let inner = Arc::get_mut(&mut arc).expect("尚未共享");
+        inner.self_ref = Some(NonNull::from(&*inner.data));
+        // SAFETY：从此刻起 Inner 只能通过共享引用访问，不会再被移动
+        SharedSelfRef {
+            inner: unsafe { Pin::new_unchecked(arc) },
+        }
+    }
+
+    /// 通过自引用读取数据，任意句柄都可以调用
+    pub fn get_ref(&self) -> Option<&T> {
+        // SAFETY：self_ref 指向 Inner 拥有的 Box<T>，只要还有句柄存在，Inner 就不会被释放
+        self.inner.self_ref.map(|ptr| unsafe { ptr.as_ref() })
+    }
+}
+
+// 克隆只增加引用计数，不复制数据，也无需修正指针
+impl<T> Clone for SharedSelfRef<T> {
+    fn clone(&self) -> Self {
+        SharedSelfRef {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{rc::Rc, string::String, vec::Vec};
+    use core::cell::Cell;
+
+    use super::SharedSelfRef;
+
+    // 析构时累加共享的计数
+    struct Tracked(Rc<Cell<usize>>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn clones_share_one_address() {
+        let shared = SharedSelfRef::new_with_ref(String::from("共享"));
+        let clones: Vec<_> = (0..3).map(|_| shared.clone()).collect();
+        for clone in &clones {
+            assert!(core::ptr::eq(clone.get_ref().unwrap(), shared.get_ref().unwrap()));
+        }
+    }
+
+    #[test]
+    fn data_is_dropped_once_after_last_handle() {
+        let drops = Rc::new(Cell::new(0));
+        let shared = SharedSelfRef::new_with_ref(Tracked(drops.clone()));
+        let another = shared.clone();
+        drop(shared);
+        assert_eq!(drops.get(), 0);
+        assert!(another.get_ref().is_some());
+        drop(another);
+        assert_eq!(drops.get(), 1);
+    }
+}
@@ -0,0 +1,196 @@
+use core::pin::Pin;
+use core::marker::{PhantomData, PhantomPinned};
+use core::ptr::{self, NonNull};
+#[cfg(feature = "allocator_api")]
+use core::alloc::Allocator;
+
+use alloc::boxed::Box;
+use super::{NoRef, WithRef};
+
+/// 数据内联存放的可选自引用容器：`Pin<Box<Self>>` 只有一次堆分配
+///
+/// `OptionalSelfRef` 把数据放在独立的 `Box<T>` 中（以便支持 `str`、`[u8]` 等不定长数据），
+/// 固定后再套一层 `Pin<Box<_>>`，构造要分配两次、`get_ref` 要跳两次指针。
+/// 这里 data 直接是结构体字段：固定后结构体地址不变，data 的地址也就不变，
+/// 自引用只在固定之后建立，因此 NoRef 状态下依然可以自由移动。
+/// 配合 [`optional_self_ref_local!`](crate::optional_self_ref_local) 固定在栈上时完全不做堆分配
+#[derive(Debug)]
+pub struct InlineSelfRef<T, S = NoRef> {
+    // 内联数据：地址随结构体变化，只有固定后才可被自引用
+    data: T,
+    // 指向自身 data 字段的裸指针
+    self_ref: Option<NonNull<T>>,
+    _state: PhantomData<S>,
+    _pin: PhantomPinned,
+}
+
+// 与 OptionalSelfRef 相同：NoRef 状态的 self_ref 恒为 None，移动安全
+impl<T> Unpin for InlineSelfRef<T, NoRef> {}
+
+// SAFETY：self_ref 只会指向自身的 data 字段，不与其他实例共享状态
+unsafe impl<T: Send, S> Send for InlineSelfRef<T, S> {}
+unsafe impl<T: Sync, S> Sync for InlineSelfRef<T, S> {}
+
+impl<T> InlineSelfRef<T, NoRef> {
+    /// 创建「无自引用」的实例，不做任何堆分配
+    pub fn new_no_ref(data: T) -> Self {
+        InlineSelfRef {
+            data,
+            self_ref: None,
+            _state: PhantomData,
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// 切换到 WithRef 状态：固定之前仍可移动
+    pub fn into_with_ref(self) -> InlineSelfRef<T, WithRef> {
+        InlineSelfRef {
+            data: self.data,
+            self_ref: None,
+            _state: PhantomData,
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// 可变访问数据：无自引用、未被固定，直接交出 `&mut T`
+    pub fn get_data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
+
+impl<T> InlineSelfRef<T, WithRef> {
+    /// 创建「有自引用」的实例：只分配一次，先固定、再建立自引用
+    pub fn new_with_ref(data: T) -> Pin<Box<Self>> {
+        let mut pinned = Box::pin(InlineSelfRef::new_no_ref(data).into_with_ref());
+        pinned.as_mut().init_self_ref();
+        pinned
+    }
+
+    /// 在指定的分配器中创建有自引用的实例（需要 nightly 与 `allocator_api` feature）
+    ///
+    /// 数据内联在容器中，唯一的一次分配来自 `A`，不经过全局分配器；
+    /// 与 `Box::pin_in` 相同要求 `A: 'static`
+    #[cfg(feature = "allocator_api")]
+    pub fn new_with_ref_in<A: Allocator + 'static>(data: T, alloc: A) -> Pin<Box<Self, A>> {
+        let mut pinned = Box::pin_in(InlineSelfRef::new_no_ref(data).into_with_ref(), alloc);
+        pinned.as_mut().init_self_ref();
+        pinned
+    }
+
+    /// 在作用域内使用有自引用的实例：容器连同内联的数据固定在当前栈帧上，
+    /// 闭包返回后清除自引用并交还数据
+    ///
+    /// 本身不做任何堆分配，只有 `T` 自己的分配（如 String 的缓冲区）。闭包 panic 时容器在原地正常析构；
+    /// 返回值不能借用闭包参数，自引用因此无法逃出作用域：
+    ///
+    /// ```
+    /// use rust_advanced::pin::InlineSelfRef;
+    ///
+    /// let (data, sum) = InlineSelfRef::scoped([1, 2, 3], |x| x.get_ref().unwrap().iter().sum::<i32>());
+    /// assert_eq!((data, sum), ([1, 2, 3], 6));
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use rust_advanced::pin::InlineSelfRef;
+    ///
+    /// let (_, escaped) = InlineSelfRef::scoped(1, |x| x.get_ref());
+    /// ```
+    ///
+    /// 数据最终被移出固定的位置，因此要求 `T: Unpin`
+    pub fn scoped<R>(data: T, f: impl FnOnce(Pin<&mut Self>) -> R) -> (T, R)
+    where
+        T: Unpin,
+    {
+        let mut slot = InlineSelfRef::new_no_ref(data).into_with_ref();
+        // SAFETY：slot 在自引用清除之前不会被移动；f 中 panic 时 slot 在原地析构
+        let mut pinned = unsafe { Pin::new_unchecked(&mut slot) };
+        pinned.as_mut().init_self_ref();
+        let result = f(pinned.as_mut());
+        // f 可能重新建立过自引用，无论如何都在这里清除；之后实例不再依赖自身地址，
+        // T: Unpin 时可以移出数据
+        pinned.clear_self_ref();
+        (slot.data, result)
+    }
+
+    /// 在已固定的实例上建立自引用；已存在自引用时不做修改，返回 `false`
+    pub fn init_self_ref(self: Pin<&mut Self>) -> bool {
+        // 仅修改字段，不移动实例
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.self_ref.is_some() {
+            return false;
+        }
+        // 从字段直接取裸指针，不经过中间引用
+        this.self_ref = NonNull::new(ptr::addr_of_mut!(this.data));
+        true
+    }
+
+    /// 清除自引用，之后实例不再依赖自身地址
+    pub fn clear_self_ref(self: Pin<&mut Self>) {
+        // 仅修改字段，不移动实例
+        unsafe { self.get_unchecked_mut() }.self_ref = None;
+    }
+
+    /// 在闭包内以 `&mut T` 修改数据，结束后若有自引用则从字段重新推导
+    ///
+    /// 闭包拿到的 `&mut T` 会使之前的自引用指针失效，因此修改后必须重新推导；
+    /// 原地 `mem::replace` 整个 `T` 会移出旧值，要求 `T: Unpin`
+    pub fn with_mut<R>(self: Pin<&mut Self>, f: impl FnOnce(&mut T) -> R) -> R
+    where
+        T: Unpin,
+    {
+        // 仅修改字段，不移动实例
+        let this = unsafe { self.get_unchecked_mut() };
+        let result = f(&mut this.data);
+        if this.self_ref.is_some() {
+            this.self_ref = NonNull::new(ptr::addr_of_mut!(this.data));
+        }
+        result
+    }
+}
+
+impl<T, S> InlineSelfRef<T, S> {
+    /// 通过自引用读取数据（仅 WithRef 状态且已固定时为 Some）
+    pub fn get_ref(&self) -> Option<&T> {
+        // SAFETY：self_ref 只在固定后建立，指向自身的 data 字段，实例不再移动
+        self.self_ref.map(|ptr| unsafe { ptr.as_ref() })
+    }
+
+    /// 直接读取数据
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::InlineSelfRef;
+
+    #[test]
+    fn self_ref_points_at_inline_field() {
+        let mut inline = InlineSelfRef::new_with_ref(String::from("内联"));
+        assert!(core::ptr::eq(inline.get_ref().unwrap(), inline.data()));
+        // 已存在自引用时不重复建立
+        assert!(!inline.as_mut().init_self_ref());
+        inline.as_mut().with_mut(|s| s.push_str("数据"));
+        assert_eq!(inline.get_ref().unwrap(), "内联数据");
+        inline.as_mut().clear_self_ref();
+        assert_eq!(inline.get_ref(), None);
+        assert!(inline.as_mut().init_self_ref());
+        assert!(core::ptr::eq(inline.get_ref().unwrap(), inline.data()));
+    }
+
+    #[test]
+    fn no_ref_moves_and_scoped_returns_data() {
+        let mut no_ref = InlineSelfRef::new_no_ref(1);
+        *no_ref.get_data_mut() += 1;
+        let moved = no_ref;
+        assert_eq!(moved.get_ref(), None);
+        let (data, seen) = InlineSelfRef::scoped(*moved.data(), |mut x| {
+            x.as_mut().with_mut(|v| *v *= 10);
+            *x.get_ref().unwrap()
+        });
+        assert_eq!((data, seen), (20, 20));
+    }
+}
@@ -0,0 +1,128 @@
+use core::cell::{Cell, Ref, RefCell, RefMut};
+use core::marker::PhantomPinned;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+use alloc::{boxed::Box, string::String};
+
+/// 通过共享引用修改内容、同时保持自引用一致的字符串容器
+///
+/// `borrow_mut_pinned` 交出的守卫可以任意修改 String（包括重新分配缓冲区），
+/// 守卫析构时从新的缓冲区重新推导 `ptr`；读取自引用时同样持有 RefCell 的借用，
+/// 因此读到的 `ptr` 不会与正在进行的修改重叠
+#[derive(Debug)]
+pub struct SelfRefCell {
+    data: Box<RefCell<String>>,
+    // 指向 data 缓冲区的胖指针；只在共享引用下修改，因此放在 Cell 中
+    ptr: Cell<NonNull<str>>,
+    _pin: PhantomPinned,
+}
+
+// SAFETY：ptr 只指向自身拥有的 String 缓冲区，随容器一起转移；
+// RefCell 与 Cell 使类型保持 !Sync，不会被跨线程共享
+unsafe impl Send for SelfRefCell {}
+
+impl SelfRefCell {
+    /// 创建固定在堆上的实例，ptr 指向 `data` 的缓冲区
+    pub fn new(data: String) -> Pin<Box<SelfRefCell>> {
+        let data = Box::new(RefCell::new(data));
+        let ptr = Cell::new(NonNull::from(data.borrow().as_str()));
+        Box::pin(SelfRefCell {
+            data,
+            ptr,
+            _pin: PhantomPinned,
+        })
+    }
+
+    /// 共享借用整个 String
+    ///
+    /// # Panics
+    /// 已有可变借用（`borrow_mut_pinned` 的守卫）存活时 panic，同 `RefCell::borrow`
+    pub fn borrow(&self) -> Ref<'_, String> {
+        self.data.borrow()
+    }
+
+    /// 通过自引用读取内容：借用期间 RefCell 保持共享借用，不会有修改同时发生
+    ///
+    /// # Panics
+    /// 已有可变借用存活时 panic
+    pub fn get_ref(&self) -> Ref<'_, str> {
+        let ptr = self.ptr.get();
+        // SAFETY：ptr 在构造和每个守卫析构时都从 data 的缓冲区重新推导；
+        // 返回的 Ref 持有共享借用，期间缓冲区不会被修改或释放
+        Ref::map(self.data.borrow(), |_| unsafe { ptr.as_ref() })
+    }
+
+    /// 可变借用 String，守卫析构时重新同步 ptr
+    ///
+    /// # Panics
+    /// 已有其他借用存活时 panic，同 `RefCell::borrow_mut`
+    pub fn borrow_mut_pinned(self: Pin<&Self>) -> SelfRefCellGuard<'_> {
+        let this = self.get_ref();
+        SelfRefCellGuard {
+            data: this.data.borrow_mut(),
+            ptr: &this.ptr,
+        }
+    }
+
+    /// 检查 ptr 是否与当前缓冲区一致（地址与长度都相同）
+    pub fn validate(&self) -> bool {
+        core::ptr::eq(self.ptr.get().as_ptr(), self.data.borrow().as_str())
+    }
+}
+
+/// [`SelfRefCell::borrow_mut_pinned`] 的守卫：解引用为 `&mut String`，析构时重新推导 ptr
+pub struct SelfRefCellGuard<'a> {
+    data: RefMut<'a, String>,
+    ptr: &'a Cell<NonNull<str>>,
+}
+
+impl Deref for SelfRefCellGuard<'_> {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.data
+    }
+}
+
+impl DerefMut for SelfRefCellGuard<'_> {
+    fn deref_mut(&mut self) -> &mut String {
+        &mut self.data
+    }
+}
+
+// 修改可能让缓冲区重新分配：RefMut 释放之前，从当前缓冲区重新推导 ptr
+impl Drop for SelfRefCellGuard<'_> {
+    fn drop(&mut self) {
+        self.ptr.set(NonNull::from(self.data.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::SelfRefCell;
+
+    #[test]
+    fn guard_resyncs_after_reallocation() {
+        let cell = SelfRefCell::new(String::from("短"));
+        {
+            let mut guard = cell.as_ref().borrow_mut_pinned();
+            // 远超容量：缓冲区重新分配
+            guard.push_str(&"长".repeat(256));
+        }
+        assert!(cell.validate());
+        assert_eq!(cell.get_ref().chars().count(), 257);
+        assert!(core::ptr::eq(&*cell.get_ref(), cell.borrow().as_str()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_during_mutation_panics() {
+        let cell = SelfRefCell::new(String::from("借用"));
+        let _guard = cell.as_ref().borrow_mut_pinned();
+        let _ = cell.get_ref();
+    }
+}
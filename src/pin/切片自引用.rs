@@ -0,0 +1,85 @@
+use core::pin::Pin;
+use core::marker::PhantomPinned;
+use core::ops::Range;
+use core::ptr::NonNull;
+
+use alloc::{boxed::Box, vec::Vec};
+use super::RangeError;
+
+/// 自引用字节切片：`window` 指向自身 `data` 的一个子区间
+#[derive(Debug)]
+pub struct SelfRefSlice {
+    // Vec 的缓冲区本身就在堆上，移动 Vec 不会改变缓冲区地址，无需再套一层 Box
+    data: Vec<u8>,
+    window: NonNull<[u8]>,
+    _pin: PhantomPinned,
+}
+
+impl SelfRefSlice {
+    /// 创建固定在堆上的实例，`window` 指向 `data[range]`；区间越界时返回错误
+    pub fn new(data: Vec<u8>, range: Range<usize>) -> Result<Pin<Box<SelfRefSlice>>, RangeError> {
+        let window = NonNull::from(checked_window(&data, range)?);
+
+        Ok(Box::pin(SelfRefSlice {
+            data,
+            window,
+            _pin: PhantomPinned,
+        }))
+    }
+
+    /// 通过自引用读取子区间
+    pub fn get_window(&self) -> &[u8] {
+        // SAFETY：window 始终指向自身 data 的缓冲区，data 固定后不再被修改
+        unsafe { self.window.as_ref() }
+    }
+
+    /// 读取完整的数据
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 重新选择子区间并同步自引用；区间越界时返回错误且保持原有窗口
+    pub fn set_window(self: Pin<&mut SelfRefSlice>, range: Range<usize>) -> Result<(), RangeError> {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.window = NonNull::from(checked_window(&this.data, range)?);
+        Ok(())
+    }
+}
+
+// 校验区间并取出对应的子切片
+fn checked_window(data: &[u8], range: Range<usize>) -> Result<&[u8], RangeError> {
+    data.get(range.clone()).ok_or(RangeError::OutOfBounds {
+        start: range.start,
+        end: range.end,
+        len: data.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::SelfRefSlice;
+    use crate::pin::RangeError;
+
+    #[test]
+    fn window_points_into_data() {
+        let mut slice = SelfRefSlice::new(vec![1, 2, 3, 4], 0..4).unwrap();
+        assert!(core::ptr::eq(slice.get_window(), slice.data()));
+        for (start, end) in [(0, 0), (1, 3), (3, 4), (4, 4)] {
+            slice.as_mut().set_window(start..end).unwrap();
+            assert!(core::ptr::eq(slice.get_window(), &slice.data()[start..end]));
+        }
+    }
+
+    #[test]
+    fn rejected_window_keeps_previous() {
+        let mut slice = SelfRefSlice::new(vec![1, 2, 3], 1..2).unwrap();
+        assert_eq!(
+            slice.as_mut().set_window(2..4),
+            Err(RangeError::OutOfBounds { start: 2, end: 4, len: 3 })
+        );
+        assert_eq!(slice.get_window(), &[2]);
+        assert!(core::ptr::eq(slice.get_window(), &slice.data()[1..2]));
+    }
+}
@@ -0,0 +1,102 @@
+use core::pin::Pin;
+use core::marker::PhantomPinned;
+
+use alloc::{boxed::Box, string::{String, ToString}};
+use super::RangeError;
+
+/// 双端自引用字符串：`head` 与 `tail` 指向同一个 `String` 的前缀与后缀
+#[derive(Debug)]
+pub struct SelfRefPair {
+    data: String,
+    // data[..split] 与 data[split..]，两个视图首尾相接、覆盖整个字符串
+    head: *const str,
+    tail: *const str,
+    _pin: PhantomPinned,
+}
+
+impl SelfRefPair {
+    /// 创建固定在堆上的实例，在 `split` 处切分；`split` 越界或不在字符边界上时返回错误
+    pub fn new(s: &str, split: usize) -> Result<Pin<Box<SelfRefPair>>, RangeError> {
+        let data = s.to_string();
+        // String 的缓冲区在堆上，移动 String 不会改变缓冲区地址，固定前即可推导指针
+        let (head, tail) = checked_split(&data, split)?;
+
+        Ok(Box::pin(SelfRefPair {
+            head: head as *const str,
+            tail: tail as *const str,
+            data,
+            _pin: PhantomPinned,
+        }))
+    }
+
+    /// 通过自引用读取前缀
+    pub fn head_str(&self) -> &str {
+        // SAFETY：head 指向自身 data 的缓冲区，data 固定后只有 resplit 会改动视图，且不修改内容
+        unsafe { &*self.head }
+    }
+
+    /// 通过自引用读取后缀
+    pub fn tail_str(&self) -> &str {
+        // SAFETY：同 head_str
+        unsafe { &*self.tail }
+    }
+
+    /// 读取完整的数据
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// 在新的位置重新切分并同步两个自引用；校验失败时返回错误且保持原有视图
+    pub fn resplit(self: Pin<&mut SelfRefPair>, split: usize) -> Result<(), RangeError> {
+        // 仅修改指针字段，不移动实例
+        let this = unsafe { self.get_unchecked_mut() };
+        let (head, tail) = checked_split(&this.data, split)?;
+        this.head = head as *const str;
+        this.tail = tail as *const str;
+        Ok(())
+    }
+}
+
+// 校验切分点并取出前缀与后缀
+fn checked_split(data: &str, split: usize) -> Result<(&str, &str), RangeError> {
+    if split > data.len() {
+        return Err(RangeError::OutOfBounds {
+            start: 0,
+            end: split,
+            len: data.len(),
+        });
+    }
+    data.split_at_checked(split)
+        .ok_or(RangeError::NotCharBoundary { index: split })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SelfRefPair;
+    use crate::pin::RangeError;
+
+    #[test]
+    fn views_cover_data_at_every_boundary() {
+        let mut pair = SelfRefPair::new("ab你c", 0).unwrap();
+        let boundaries: alloc::vec::Vec<usize> = (0..=pair.data().len())
+            .filter(|&i| pair.data().is_char_boundary(i))
+            .collect();
+        for split in boundaries {
+            pair.as_mut().resplit(split).unwrap();
+            let data = pair.data();
+            assert_eq!((pair.head_str(), pair.tail_str()), data.split_at(split));
+            assert_eq!(pair.head_str().as_ptr(), data.as_ptr());
+            assert_eq!(pair.tail_str().as_ptr(), data[split..].as_ptr());
+        }
+    }
+
+    #[test]
+    fn rejected_resplit_keeps_views() {
+        let mut pair = SelfRefPair::new("你好", 3).unwrap();
+        let (head, tail) = (pair.head_str() as *const str, pair.tail_str() as *const str);
+        assert_eq!(pair.as_mut().resplit(4), Err(RangeError::NotCharBoundary { index: 4 }));
+        assert_eq!(pair.as_mut().resplit(7), Err(RangeError::OutOfBounds { start: 0, end: 7, len: 6 }));
+        assert!(core::ptr::eq(pair.head_str(), head) && core::ptr::eq(pair.tail_str(), tail));
+        assert_eq!((pair.head_str(), pair.tail_str()), ("你", "好"));
+    }
+}
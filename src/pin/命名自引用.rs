@@ -0,0 +1,123 @@
+use core::marker::PhantomPinned;
+use core::ops::Range;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+use alloc::{boxed::Box, collections::BTreeMap, string::{String, ToString}};
+use super::self_ref::check_range;
+use super::RangeError;
+
+/// 一份自身拥有的字符串 + 多个具名的子串视图（如 URL 的 host / path / query）
+///
+/// 每个视图保存指向 `data` 缓冲区的指针与对应的字节区间：读取时直接解引用指针，
+/// 替换内容后按区间重新校验，仍然有效的视图从新缓冲区重新推导，失效的视图被移除。
+/// 名称按字典序保存（`BTreeMap`），`#![no_std]` 下同样可用
+#[derive(Debug)]
+pub struct NamedSelfRef {
+    data: String,
+    views: BTreeMap<String, (NonNull<str>, Range<usize>)>,
+    _pin: PhantomPinned,
+}
+
+impl NamedSelfRef {
+    /// 创建固定在堆上、尚无具名视图的实例
+    pub fn new(data: impl Into<String>) -> Pin<Box<Self>> {
+        Box::pin(NamedSelfRef {
+            data: data.into(),
+            views: BTreeMap::new(),
+            _pin: PhantomPinned,
+        })
+    }
+
+    /// 把 `data[range]` 命名为 `name`，同名视图会被替换；
+    /// 区间越界或端点不在字符边界上时返回错误，已有视图保持不变
+    pub fn name_range(self: Pin<&mut Self>, name: &str, range: Range<usize>) -> Result<(), RangeError> {
+        // 仅修改 views，不移动实例
+        let this = unsafe { self.get_unchecked_mut() };
+        check_range(&this.data, &range)?;
+        let view = NonNull::from(&this.data[range.clone()]);
+        this.views.insert(name.to_string(), (view, range));
+        Ok(())
+    }
+
+    /// 按名称读取子串视图
+    pub fn get_named(&self, name: &str) -> Option<&str> {
+        let (view, range) = self.views.get(name)?;
+        // SAFETY：view 指向自身 data 的缓冲区，每次替换内容后都会重新推导或移除
+        let view = unsafe { view.as_ref() };
+        debug_assert_eq!(view.len(), range.len());
+        Some(view)
+    }
+
+    /// 所有视图的名称（字典序）
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.views.keys().map(String::as_str)
+    }
+
+    /// 视图对应的字节区间
+    pub fn range_of(&self, name: &str) -> Option<Range<usize>> {
+        self.views.get(name).map(|(_, range)| range.clone())
+    }
+
+    /// 读取完整的数据
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// 替换内容：区间在新内容上仍然有效的视图重新指向新缓冲区，失效的视图被移除
+    pub fn update_data(self: Pin<&mut Self>, new_content: impl Into<String>) {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.data = new_content.into();
+        let data = &this.data;
+        this.views.retain(|_, (view, range)| {
+            if check_range(data, range).is_err() {
+                return false;
+            }
+            *view = NonNull::from(&data[range.clone()]);
+            true
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::NamedSelfRef;
+    use crate::pin::RangeError;
+
+    #[test]
+    fn named_views_point_into_data() {
+        let mut url = NamedSelfRef::new("example.com/路径?q=1");
+        url.as_mut().name_range("host", 0..11).unwrap();
+        url.as_mut().name_range("path", 12..18).unwrap();
+        assert_eq!(url.get_named("host"), Some("example.com"));
+        assert_eq!(url.get_named("path"), Some("路径"));
+        assert!(core::ptr::eq(url.get_named("path").unwrap(), &url.data()[12..18]));
+        assert_eq!(url.names().collect::<Vec<_>>(), ["host", "path"]);
+        assert_eq!(url.range_of("path"), Some(12..18));
+        assert_eq!(url.get_named("query"), None);
+    }
+
+    #[test]
+    fn invalid_range_keeps_existing_views() {
+        let mut text = NamedSelfRef::new("你好");
+        text.as_mut().name_range("first", 0..3).unwrap();
+        assert_eq!(text.as_mut().name_range("first", 0..1), Err(RangeError::NotCharBoundary { index: 1 }));
+        assert!(matches!(text.as_mut().name_range("x", 0..9), Err(RangeError::OutOfBounds { .. })));
+        assert_eq!(text.get_named("first"), Some("你"));
+        assert_eq!(text.names().count(), 1);
+    }
+
+    #[test]
+    fn update_data_rederives_or_drops_views() {
+        let mut text = NamedSelfRef::new("abcdef");
+        text.as_mut().name_range("head", 0..2).unwrap();
+        text.as_mut().name_range("tail", 4..6).unwrap();
+        text.as_mut().update_data("xyz");
+        // 仍然有效的视图指向新缓冲区，越界的视图被移除
+        assert_eq!(text.get_named("head"), Some("xy"));
+        assert!(core::ptr::eq(text.get_named("head").unwrap(), &text.data()[0..2]));
+        assert_eq!(text.get_named("tail"), None);
+    }
+}
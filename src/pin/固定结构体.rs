@@ -0,0 +1,930 @@
+use core::pin::Pin;
+use core::marker::PhantomPinned;
+#[cfg(feature = "allocator_api")]
+use core::alloc::Allocator;
+use core::mem;
+use core::ops::{Deref, Range};
+use core::ptr::NonNull;
+use core::fmt;
+use core::str::{CharIndices, Chars};
+
+use alloc::{boxed::Box, format, string::{String, ToString}};
+use super::{InvariantViolation, OptionalSelfRef, RangeError, SelfRefError, WithRef};
+
+/// 自引用字符串：`ptr` 指向自身 `data` 的内容（或用 [`set_range`](Self::set_range) 选定的子串），
+/// 必须通过 `Pin<Box<SelfRef>>` 使用
+///
+/// 实现了 `Send`，可以把固定的 Box 整个交给其他线程；但不实现 `Sync`：
+///
+/// ```compile_fail
+/// use rust_advanced::pin::SelfRef;
+///
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<SelfRef>();
+/// ```
+pub struct SelfRef {
+    data: String,
+    // None 表示尚未初始化（见 new_uninit / init）
+    ptr: Option<NonNull<str>>,
+    // ptr 指向的子串区间，None 表示整个字符串
+    range: Option<Range<usize>>,
+    // find_and_pin 安装的模式：替换内容后据此重新查找区间
+    pattern: Option<String>,
+    // update_in_place 复用原缓冲区的次数，只在 debug 构建中计数
+    #[cfg(debug_assertions)]
+    buffer_reuses: usize,
+    _pin: PhantomPinned,
+}
+
+/// [`SelfRef::push_str`] 的结果：缓冲区是否因超出容量而重新分配
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppendOutcome {
+    /// 容量足够，原地追加，缓冲区地址不变
+    InPlace,
+    /// 超出容量，缓冲区重新分配，ptr 已重新推导
+    Reallocated,
+}
+
+// SAFETY：ptr 只指向自身 data 的堆缓冲区，这块内存由 String 独占、随 SelfRef 一起转移，
+// 不与任何其他值共享，也不依赖线程局部状态，因此整体移动到其他线程是安全的。
+// 刻意不实现 Sync，保持 NonNull 带来的 !Sync
+unsafe impl Send for SelfRef {}
+
+impl SelfRef {
+    /// 创建固定在堆上的自引用实例：`&str` 会被复制，已有的 `String` 直接移入、不复制缓冲区
+    ///
+    /// # Panics
+    /// `s` 为空字符串时 panic，需要处理空输入时请用 [`try_new`](Self::try_new)
+    pub fn new(s: impl Into<String>) -> Pin<Box<SelfRef>> {
+        match Self::try_new(s) {
+            Ok(pinned) => pinned,
+            Err(err) => panic!("SelfRef::new 失败：{}", err),
+        }
+    }
+
+    /// 创建固定在堆上的自引用实例，拒绝空字符串，保证构造后 `get_ref` 不为空
+    pub fn try_new(s: impl Into<String>) -> Result<Pin<Box<SelfRef>>, SelfRefError> {
+        let data = s.into();
+        if data.is_empty() {
+            return Err(SelfRefError::Empty);
+        }
+        Ok(Self::from_string(data))
+    }
+
+    /// 在指定的分配器中创建固定的实例（需要 nightly 与 `allocator_api` feature）
+    ///
+    /// 分配器只用于 SelfRef 结构体本身，String 的缓冲区仍由全局分配器分配；
+    /// 与 `Box::pin_in` 相同要求 `A: 'static`
+    ///
+    /// # Panics
+    /// `s` 为空字符串时 panic，需要处理空输入时请用 [`try_new_in`](Self::try_new_in)
+    #[cfg(feature = "allocator_api")]
+    pub fn new_in<A: Allocator + 'static>(s: impl Into<String>, alloc: A) -> Pin<Box<SelfRef, A>> {
+        match Self::try_new_in(s, alloc) {
+            Ok(pinned) => pinned,
+            Err(err) => panic!("SelfRef::new_in 失败：{}", err),
+        }
+    }
+
+    /// 在指定的分配器中创建固定的实例，拒绝空字符串（需要 nightly 与 `allocator_api` feature）
+    #[cfg(feature = "allocator_api")]
+    pub fn try_new_in<A: Allocator + 'static>(
+        s: impl Into<String>,
+        alloc: A,
+    ) -> Result<Pin<Box<SelfRef, A>>, SelfRefError> {
+        let data = s.into();
+        if data.is_empty() {
+            return Err(SelfRefError::Empty);
+        }
+        let mut pinned = Box::pin_in(SelfRef {
+            data,
+            ptr: None,
+            range: None,
+            pattern: None,
+            #[cfg(debug_assertions)]
+            buffer_reuses: 0,
+            _pin: PhantomPinned,
+        }, alloc);
+        pinned.as_mut().init();
+        Ok(pinned)
+    }
+
+    // 不做校验的构造：先以未初始化的状态固定，再从固定位置上的 data 推导 ptr
+    pub(crate) fn from_string(data: String) -> Pin<Box<SelfRef>> {
+        let mut pinned = Self::pin_uninit(data);
+        pinned.as_mut().init();
+        pinned
+    }
+
+    // 以未初始化的状态固定：ptr 为空，等待 init
+    fn pin_uninit(data: String) -> Pin<Box<SelfRef>> {
+        Box::pin(SelfRef {
+            data,
+            ptr: None,
+            range: None,
+            pattern: None,
+            #[cfg(debug_assertions)]
+            buffer_reuses: 0,
+            _pin: PhantomPinned,
+        })
+    }
+
+    /// 两阶段构造：创建内容为空、尚未建立自引用的固定实例
+    ///
+    /// 可先用 `push_str` 等方法写入内容，再调用 [`init`](Self::init) 建立自引用；
+    /// 初始化之前 [`try_get_ref`](Self::try_get_ref) 返回 `None`，修改方法也不会建立自引用
+    pub fn new_uninit() -> Pin<Box<SelfRef>> {
+        Self::pin_uninit(String::new())
+    }
+
+    /// 建立（或重新建立）自引用，指向当前的缓冲区
+    pub fn init(mut self: Pin<&mut SelfRef>) {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        this.ptr = Some(NonNull::from(this.target()));
+        debug_verify!(self);
+    }
+
+    /// 只固定一段子串：让 ptr 指向 `data[range]`，之后 `get_ref` 只返回这段子串
+    ///
+    /// 区间越界或端点不在字符边界上时返回错误，原有的区间保持不变。
+    /// `update_data` / `update_in_place` 替换内容后，区间在新内容上仍然有效（不越界且在字符边界上）
+    /// 时重新应用，否则恢复为整个字符串；`push_str` 只追加，区间始终保留
+    pub fn set_range(mut self: Pin<&mut SelfRef>, range: Range<usize>) -> Result<(), RangeError> {
+        check_range(&self.data, &range)?;
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        // 显式的区间取代之前安装的模式
+        this.pattern = None;
+        this.range = Some(range);
+        this.sync_ptr();
+        debug_verify!(self);
+        Ok(())
+    }
+
+    /// 取消区间（以及 `find_and_pin` 安装的模式），ptr 重新指向整个字符串
+    pub fn clear_range(mut self: Pin<&mut SelfRef>) {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        this.range = None;
+        this.pattern = None;
+        this.sync_ptr();
+        debug_verify!(self);
+    }
+
+    /// 当前选定的子串区间，`None` 表示整个字符串
+    pub fn range(&self) -> Option<Range<usize>> {
+        self.range.clone()
+    }
+
+    /// 查找 `pattern` 的第一次出现，让 ptr 只指向匹配到的子串，返回是否找到
+    ///
+    /// 没有找到时原有的自引用与区间保持不变。找到后模式会被保存：之后每次修改内容都会重新查找，
+    /// 匹配消失时恢复为整个字符串，但模式仍然保留，内容再次包含它时自动重新指向；
+    /// [`set_range`](Self::set_range) / [`clear_range`](Self::clear_range) 会移除模式
+    pub fn find_and_pin(mut self: Pin<&mut SelfRef>, pattern: &str) -> bool {
+        let Some(start) = self.data.find(pattern) else {
+            return false;
+        };
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        this.range = Some(start..start + pattern.len());
+        this.pattern = Some(pattern.to_string());
+        this.sync_ptr();
+        debug_verify!(self);
+        true
+    }
+
+    /// `find_and_pin` 安装的模式
+    pub fn pattern(&self) -> Option<&str> {
+        self.pattern.as_deref()
+    }
+
+    /// 自引用是否已建立
+    pub fn is_init(&self) -> bool {
+        self.ptr.is_some()
+    }
+
+    /// 通过自引用指针读取内容，尚未初始化时返回 `None`
+    pub fn try_get_ref(&self) -> Option<&str> {
+        debug_assert!(self.validate(), "ptr 与 data 的缓冲区不一致");
+        // SAFETY：ptr 始终指向自身 data 的缓冲区，每次修改 data 后都会重新同步
+        self.ptr.map(|ptr| unsafe { ptr.as_ref() })
+    }
+
+    /// 通过自引用指针读取内容，设置了区间时只返回对应的子串
+    ///
+    /// # Panics
+    /// 尚未初始化（[`new_uninit`](Self::new_uninit) 之后未调用 [`init`](Self::init)）时 panic，
+    /// 信息中包含结构体地址；需要处理这种情况时用 [`try_get_ref`](Self::try_get_ref)
+    pub fn get_ref(&self) -> &str {
+        match self.try_get_ref() {
+            Some(s) => s,
+            None => panic!("SelfRef 尚未初始化（结构体地址 {:p}），请先调用 init", self as *const Self),
+        }
+    }
+
+    /// 取得读取守卫：每次解引用都在 debug 构建下重新校验 ptr 与缓冲区是否一致
+    ///
+    /// 守卫借用 `&self`，不能比实例活得更久：
+    ///
+    /// ```compile_fail
+    /// use rust_advanced::pin::SelfRef;
+    ///
+    /// let view = {
+    ///     let sr = SelfRef::new("守卫");
+    ///     sr.view()
+    /// };
+    /// println!("{}", &*view);
+    /// ```
+    ///
+    /// # Panics
+    /// 尚未初始化时 panic，同 [`get_ref`](Self::get_ref)
+    pub fn view(&self) -> SelfRefView<'_> {
+        if !self.is_init() {
+            panic!("SelfRef 尚未初始化（结构体地址 {:p}），请先调用 init", self as *const Self);
+        }
+        SelfRefView { owner: self }
+    }
+
+    /// 在闭包内通过自引用读取内容，解引用集中在 [`get_ref`](Self::get_ref)
+    pub fn with_ref<R>(&self, f: impl FnOnce(&str) -> R) -> R {
+        f(self.get_ref())
+    }
+
+    /// 按空白切分，逐个交出固定缓冲区中的子切片；切片借用 `&self`，缓冲区固定期间不会移动
+    ///
+    /// ```
+    /// use rust_advanced::pin::SelfRef;
+    ///
+    /// let pinned = SelfRef::new("  a  bb ccc ");
+    /// let tokens: Vec<_> = pinned.split_whitespace_pinned().collect();
+    /// assert_eq!(tokens, ["a", "bb", "ccc"]);
+    /// ```
+    pub fn split_whitespace_pinned(&self) -> impl Iterator<Item = &str> {
+        self.get_ref().split_whitespace()
+    }
+
+    /// 逐个字符遍历 ptr 指向的内容，不产生中间分配；迭代器借用 `&self`
+    pub fn chars_pinned(&self) -> Chars<'_> {
+        self.get_ref().chars()
+    }
+
+    /// 逐个字符遍历 ptr 指向的内容，同时给出每个字符的字节偏移
+    ///
+    /// ```
+    /// use rust_advanced::pin::SelfRef;
+    ///
+    /// let pinned = SelfRef::new("固定a");
+    /// assert_eq!(pinned.chars_pinned().count(), 3);
+    /// let offsets: Vec<_> = pinned.char_indices_pinned().map(|(i, _)| i).collect();
+    /// assert_eq!(offsets, [0, 3, 6]);
+    /// ```
+    pub fn char_indices_pinned(&self) -> CharIndices<'_> {
+        self.get_ref().char_indices()
+    }
+
+    /// 检查 ptr 是否仍与 data 的缓冲区一致（地址与长度都相同）
+    ///
+    /// 修改 data 后忘记同步 ptr 是自引用最常见的错误，`get_ref` 在 debug 构建下会以此断言
+    pub fn validate(&self) -> bool {
+        self.verify_invariant().is_ok()
+    }
+
+    /// 校验不变量：ptr 的起始地址与长度都必须与 data 当前的缓冲区（设置了区间时为对应的子串）一致，
+    /// 失败时给出期望与实际值；尚未初始化时没有 ptr，视为一致
+    pub fn verify_invariant(&self) -> Result<(), InvariantViolation> {
+        let Some(ptr) = self.ptr else {
+            return Ok(());
+        };
+        let target = self.target();
+        let expected = target.as_ptr() as usize;
+        let actual = ptr.as_ptr() as *const u8 as usize;
+        if actual != expected {
+            return Err(InvariantViolation::AddressMismatch { expected, actual });
+        }
+        // 只读取胖指针中的长度元数据，不解引用
+        let len = (ptr.as_ptr() as *const [u8]).len();
+        if len != target.len() {
+            return Err(InvariantViolation::LengthMismatch {
+                expected: target.len(),
+                actual: len,
+            });
+        }
+        Ok(())
+    }
+
+    /// 直接读取 `data`（不经过自引用指针）
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// 字节长度（与 `str::len` 相同，不是字符数）
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// 内容是否为空
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// 缓冲区容量：追加后的长度不超过它时，`push_str` 不会重新分配
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// [`update_in_place`](Self::update_in_place) 复用原缓冲区（未重新分配）的次数
+    ///
+    /// 与 [`live_self_ref_count`](super::live_self_ref_count) 一样只在 debug 构建中计数，
+    /// release 构建不维护计数，始终返回 0
+    pub fn buffer_reused_count(&self) -> usize {
+        #[cfg(debug_assertions)]
+        return self.buffer_reuses;
+        #[cfg(not(debug_assertions))]
+        0
+    }
+
+    /// 以字节切片读取内容
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_bytes()
+    }
+
+    /// 替换内容并同步自引用指针
+    pub fn update_data(mut self: Pin<&mut SelfRef>, new_content: &str) {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        this.data = new_content.to_string();
+        this.revalidate_range();
+        this.sync_ptr();
+        debug_verify!(self);
+    }
+
+    /// 原地替换内容：复用现有缓冲区，容量足够时缓冲区地址不变
+    ///
+    /// 与 `update_data` 不同，这里 `clear` + `push_str`，不分配新的 String；
+    /// 只有新内容超出容量时才会重新分配。ptr 是带长度的胖指针，并且旧指针的来源借用
+    /// 已被写入作废，因此仍从 data 重新推导——容量足够时推导出的地址与原来相同。
+    /// 可用 [`buffer_reused_count`](Self::buffer_reused_count) 观察复用次数
+    pub fn update_in_place(mut self: Pin<&mut SelfRef>, new_content: &str) {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        #[cfg(debug_assertions)]
+        let before = this.data.as_ptr();
+        this.data.clear();
+        this.data.push_str(new_content);
+        #[cfg(debug_assertions)]
+        if this.data.as_ptr() == before {
+            this.buffer_reuses += 1;
+        }
+        this.revalidate_range();
+        this.sync_ptr();
+        debug_verify!(self);
+    }
+
+    /// 追加内容，只在必要时修正 ptr，并报告缓冲区是否重新分配
+    ///
+    /// 超出容量时 String 会重新分配，ptr 必须重新推导；原地追加时：
+    /// - 指向子串区间（[`set_range`](Self::set_range)）的 ptr 地址与长度都不变，无需修正；
+    /// - 指向整个字符串的 ptr 是带长度的胖指针，长度变了，仍然要重新推导；
+    /// - 安装了模式（[`find_and_pin`](Self::find_and_pin)）时重新查找，追加的内容可能让匹配重新出现。
+    ///
+    /// 追加空串什么也不做，返回 [`AppendOutcome::InPlace`]
+    pub fn push_str(mut self: Pin<&mut SelfRef>, extra: &str) -> AppendOutcome {
+        if extra.is_empty() {
+            return AppendOutcome::InPlace;
+        }
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        let before = this.data.as_ptr();
+        this.data.push_str(extra);
+        let outcome = if this.data.as_ptr() == before {
+            AppendOutcome::InPlace
+        } else {
+            AppendOutcome::Reallocated
+        };
+        if outcome == AppendOutcome::Reallocated || this.range.is_none() || this.pattern.is_some() {
+            this.revalidate_range();
+            this.sync_ptr();
+        }
+        debug_verify!(self);
+        outcome
+    }
+
+    /// 仅供演示与测试：让 ptr 指向缓冲区内 `range` 对应的子串，制造不一致的自引用
+    ///
+    /// ptr 仍落在缓冲区内，之后任何 `update_data` / `push_str` 都会重新同步
+    ///
+    /// 只在 debug 构建且启用 `test-utils` feature（或本库自身的单元测试）时编译，不属于公开 API
+    ///
+    /// # Safety
+    /// 调用后、重新同步之前不得在 release 构建中调用 `get_ref`，也不得释放实例
+    /// （debug 构建下析构会断言失败；由于其他 panic 而 unwind 时跳过断言）
+    #[doc(hidden)]
+    #[cfg(all(debug_assertions, any(test, feature = "test-utils")))]
+    pub unsafe fn debug_retarget_ptr(self: Pin<&mut SelfRef>, range: Range<usize>) {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.ptr = Some(NonNull::from(&this.data[range]));
+    }
+
+    /// 克隆为新的固定实例：数据复制到新的缓冲区，ptr 从克隆体自己的缓冲区重新推导，
+    /// 子串区间按相同偏移保留，尚未初始化的实例克隆后同样未初始化
+    ///
+    /// 不实现 `Clone`：派生的克隆会原样复制 ptr，让克隆体指向原实例的缓冲区
+    ///
+    /// ```
+    /// use rust_advanced::pin::SelfRef;
+    ///
+    /// let mut original = SelfRef::new("克隆的子串");
+    /// original.as_mut().set_range(6..12).unwrap();
+    /// let cloned = original.as_ref().clone_pinned();
+    /// assert_eq!(cloned.get_ref(), "的子");
+    /// assert_ne!(cloned.get_ref().as_ptr(), original.get_ref().as_ptr());
+    /// drop(original);
+    /// assert_eq!(cloned.get_ref(), "的子");
+    /// ```
+    pub fn clone_pinned(self: Pin<&SelfRef>) -> Pin<Box<SelfRef>> {
+        // update_data 可能已把内容改为空串，克隆时原样复制，不再校验
+        let mut cloned = SelfRef::from_string(self.data.clone());
+        // 仅修改字段，不移动实例
+        let this = unsafe { cloned.as_mut().get_unchecked_mut() };
+        this.range = self.range.clone();
+        this.pattern = self.pattern.clone();
+        if self.ptr.is_none() {
+            this.ptr = None;
+        }
+        this.sync_ptr();
+        cloned
+    }
+
+    /// 消费固定的实例，取回内部的 String
+    ///
+    /// 整个结构体随之销毁，ptr 不会再被使用；String 的缓冲区原样转交，无需复制，
+    /// 其余字段（如 `find_and_pin` 安装的模式）随结构体正常析构
+    pub fn into_string(pinned: Pin<Box<SelfRef>>) -> String {
+        // SAFETY：只从 Box 中取走 data，结构体本身不被移动，随后在原地析构
+        let mut this = unsafe { Pin::into_inner_unchecked(pinned) };
+        // 先清除 ptr：data 被取走后旧指针不再有效，析构时的校验不应把它当作不一致
+        this.ptr = None;
+        mem::take(&mut this.data)
+    }
+
+    /// 单行描述：结构体地址、缓冲区地址、ptr 目标与内容，内容放在最后
+    ///
+    /// 多行的地址报告见 `{:#}` 格式的 Display
+    pub fn describe(&self) -> String {
+        let target = match self.ptr {
+            Some(ptr) => format!("{:p}", ptr.as_ptr() as *const u8),
+            None => String::from("未初始化"),
+        };
+        format!(
+            "结构体地址: {:p}，缓冲区地址: {:p}，ptr 目标: {}，内容: {}",
+            self as *const Self,
+            self.data.as_ptr(),
+            target,
+            self.data
+        )
+    }
+
+    /// 获取 SelfRef 结构体本身的地址（证明 Pin 固定）
+    pub fn get_struct_addr(&self) -> *const SelfRef {
+        self as *const SelfRef
+    }
+
+    // 修改 data 之后重新推导 ptr；尚未初始化的实例保持未初始化，等待 init
+    fn sync_ptr(&mut self) {
+        if self.ptr.is_some() {
+            self.ptr = Some(NonNull::from(self.target()));
+        }
+    }
+
+    // ptr 应当指向的内容：选定的子串，或整个字符串
+    fn target(&self) -> &str {
+        match &self.range {
+            Some(range) => &self.data[range.clone()],
+            None => &self.data,
+        }
+    }
+
+    // 修改内容之后：安装了模式时重新查找；否则区间在新内容上已无效时恢复为整个字符串
+    fn revalidate_range(&mut self) {
+        if let Some(pattern) = &self.pattern {
+            self.range = self.data.find(pattern.as_str()).map(|start| start..start + pattern.len());
+            return;
+        }
+        if let Some(range) = &self.range {
+            if check_range(&self.data, range).is_err() {
+                self.range = None;
+            }
+        }
+    }
+}
+
+/// [`SelfRef::view`] 的守卫：解引用为 `&str`，debug 构建下每次解引用前校验不变量
+pub struct SelfRefView<'a> {
+    owner: &'a SelfRef,
+}
+
+impl Deref for SelfRefView<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        // 不同步的 ptr 在解引用之前就被发现，而不是读出错误的内容
+        #[cfg(debug_assertions)]
+        if let Err(violation) = self.owner.verify_invariant() {
+            panic!("SelfRefView：ptr 与缓冲区不同步，{}", violation);
+        }
+        let ptr = self.owner.ptr.expect("view 只为已初始化的实例创建");
+        // SAFETY：ptr 指向 owner 的缓冲区，守卫借用 owner，期间缓冲区不会被修改或释放
+        unsafe { ptr.as_ref() }
+    }
+}
+
+impl fmt::Debug for SelfRefView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for SelfRefView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+// Debug 报告 ptr 与缓冲区的关系：invariant_ok 要求地址与长度都一致（同 validate），
+// in_buffer 只检查 ptr 的起始地址是否落在缓冲区范围内
+impl fmt::Debug for SelfRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target = self.ptr.map(|ptr| ptr.as_ptr() as *const u8);
+        let buffer = self.data.as_bytes().as_ptr_range();
+        f.debug_struct("SelfRef")
+            .field("data", &self.data)
+            .field("data_addr", &buffer.start)
+            .field("ptr", &target)
+            .field("range", &self.range)
+            .field("pattern", &self.pattern)
+            .field("invariant_ok", &self.validate())
+            .field("in_buffer", &target.is_some_and(|target| buffer.contains(&target) || target == buffer.start))
+            .finish()
+    }
+}
+
+// 默认只打印内容；`{:#}` 输出多行的地址报告：结构体、缓冲区、ptr 目标及是否一致
+impl fmt::Display for SelfRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.data)?;
+        if f.alternate() {
+            writeln!(f)?;
+            writeln!(f, "  结构体地址: {:p}", self as *const Self)?;
+            writeln!(f, "  缓冲区地址: {:p}", self.data.as_ptr())?;
+            match self.ptr {
+                Some(ptr) => writeln!(f, "  ptr 目标: {:p}", ptr.as_ptr() as *const u8)?,
+                None => writeln!(f, "  ptr 目标: 未初始化")?,
+            }
+            write!(f, "  地址一致: {}", self.validate())?;
+        }
+        Ok(())
+    }
+}
+
+// 相等性只比较内容：两个独立构造的实例指针必然不同，指针身份刻意不参与比较
+impl PartialEq for SelfRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl Eq for SelfRef {}
+
+// 标准转换：与 try_new 对应，空串时返回错误；&str 复制内容，String 直接移入（缓冲区不复制）
+impl TryFrom<&str> for Pin<Box<SelfRef>> {
+    type Error = SelfRefError;
+
+    fn try_from(s: &str) -> Result<Self, SelfRefError> {
+        SelfRef::try_new(s)
+    }
+}
+
+impl TryFrom<String> for Pin<Box<SelfRef>> {
+    type Error = SelfRefError;
+
+    fn try_from(data: String) -> Result<Self, SelfRefError> {
+        SelfRef::try_new(data)
+    }
+}
+
+// 与 OptionalSelfRef 互相转换：消费源实例取出 String（源实例的指针随之销毁），
+// 移入目标类型的新分配后，在新的位置上重建自引用
+impl From<Pin<Box<SelfRef>>> for Pin<Box<OptionalSelfRef<String, WithRef>>> {
+    fn from(pinned: Pin<Box<SelfRef>>) -> Self {
+        OptionalSelfRef::new_with_ref(SelfRef::into_string(pinned))
+    }
+}
+
+// SelfRef 不允许空内容，因此反方向可能失败
+impl TryFrom<Pin<Box<OptionalSelfRef<String, WithRef>>>> for Pin<Box<SelfRef>> {
+    type Error = SelfRefError;
+
+    fn try_from(pinned: Pin<Box<OptionalSelfRef<String, WithRef>>>) -> Result<Self, SelfRefError> {
+        let data = OptionalSelfRef::into_data(pinned);
+        if data.is_empty() {
+            return Err(SelfRefError::Empty);
+        }
+        Ok(SelfRef::from_string(data))
+    }
+}
+
+// 析构时的诊断：debug 构建下先断言 ptr 仍有效（正在 panic 时跳过，避免 unwind 期间再次 panic
+// 直接中止进程；no_std 下无法得知是否正在 panic，不做检查），再把 ptr 改成指向静态空串的哨兵，
+// ptr 不再保留即将释放的缓冲区地址，绕过 Pin（如 into_inner_unchecked 后重复析构）时更容易被识别；
+// 哨兵是 Some，与「尚未初始化」的 None 区分开
+//
+// Drop::drop 对 !Unpin 类型同样拿到 &mut self，这里是可靠的：Pin 的约定只要求固定的值在析构
+// 之前不被移动，drop 只改写 ptr 字段、不移动任何字段；析构结束后内存才被释放
+impl Drop for SelfRef {
+    fn drop(&mut self) {
+        #[cfg(all(debug_assertions, feature = "std"))]
+        if !std::thread::panicking() {
+            assert!(self.validate(), "析构前 ptr 已与 data 的缓冲区不一致");
+        }
+        self.ptr = Some(NonNull::from(DROPPED));
+    }
+}
+
+// 析构后 ptr 指向的哨兵
+const DROPPED: &str = "";
+
+// 校验子串区间：端点必须在字符边界上，且不越界
+pub(super) fn check_range(text: &str, range: &Range<usize>) -> Result<(), RangeError> {
+    for index in [range.start, range.end] {
+        if index <= text.len() && !text.is_char_boundary(index) {
+            return Err(RangeError::NotCharBoundary { index });
+        }
+    }
+    match text.get(range.clone()) {
+        Some(_) => Ok(()),
+        None => Err(RangeError::OutOfBounds {
+            start: range.start,
+            end: range.end,
+            len: text.len(),
+        }),
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_support {
+    use super::*;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    // 序列化格式：内容 + ptr 所指子串的字节偏移与长度 + 是否已初始化，绝不保存裸指针
+    #[derive(Serialize)]
+    #[serde(rename = "SelfRef")]
+    struct ReprRef<'a> {
+        data: &'a str,
+        offset: usize,
+        len: usize,
+        init: bool,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "SelfRef")]
+    struct Repr {
+        data: String,
+        offset: usize,
+        len: usize,
+        init: bool,
+    }
+
+    // 指向整个字符串时写 0..len；空内容与尚未初始化的状态都原样写出，反序列化后保持不变
+    impl Serialize for SelfRef {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let range = self.range.clone().unwrap_or(0..self.data.len());
+            ReprRef {
+                data: &self.data,
+                offset: range.start,
+                len: range.len(),
+                init: self.is_init(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    /// 反序列化辅助类型：`Pin<Box<SelfRef>>` 无法直接实现 `Deserialize`，
+    /// 通过它反序列化后用 `.0` 取出固定的实例，ptr 按保存的偏移在新缓冲区上重建
+    ///
+    /// 空内容（如 `update_data("")` 之后）与尚未初始化的实例同样可以往返；
+    /// 偏移越界或不在字符边界上时反序列化报错，不会产生错误的指针：
+    ///
+    /// ```
+    /// use rust_advanced::pin::{SelfRef, SelfRefDef};
+    ///
+    /// let mut sr = SelfRef::new("持久化的子串");
+    /// sr.as_mut().set_range(12..18).unwrap();
+    /// let json = serde_json::to_string(&*sr).unwrap();
+    /// let SelfRefDef(restored) = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(restored.get_ref(), "子串");
+    ///
+    /// let corrupted = r#"{"data":"持久化","offset":1,"len":3,"init":true}"#;
+    /// assert!(serde_json::from_str::<SelfRefDef>(corrupted).is_err());
+    /// ```
+    #[derive(Debug)]
+    pub struct SelfRefDef(pub Pin<Box<SelfRef>>);
+
+    impl Serialize for SelfRefDef {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            (*self.0).serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for SelfRefDef {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = Repr::deserialize(deserializer)?;
+            let end = repr
+                .offset
+                .checked_add(repr.len)
+                .ok_or_else(|| D::Error::custom("偏移与长度之和溢出"))?;
+            let whole = repr.offset == 0 && end == repr.data.len();
+            // 先以未初始化的状态固定，区间通过校验后才建立 ptr
+            let mut pinned = SelfRef::pin_uninit(repr.data);
+            if !whole {
+                pinned.as_mut().set_range(repr.offset..end).map_err(D::Error::custom)?;
+            }
+            if repr.init {
+                pinned.as_mut().init();
+            }
+            Ok(SelfRefDef(pinned))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn round_trip(original: &SelfRef) -> Pin<Box<SelfRef>> {
+            let json = serde_json::to_string(original).unwrap();
+            let SelfRefDef(restored) = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&*restored).unwrap(), json);
+            restored
+        }
+
+        #[test]
+        fn whole_string_round_trips() {
+            let original = SelfRef::new("整个字符串");
+            let restored = round_trip(&original);
+            assert_eq!(restored.get_ref(), "整个字符串");
+            assert_eq!(restored.range(), None);
+            assert!(restored.validate());
+        }
+
+        #[test]
+        fn sub_range_round_trips_into_new_buffer() {
+            let mut original = SelfRef::new("前缀|子串|后缀");
+            original.as_mut().set_range(7..13).unwrap();
+            let restored = round_trip(&original);
+            assert_eq!(restored.get_ref(), "子串");
+            assert_eq!(restored.range(), Some(7..13));
+            assert!(restored.data().as_bytes().as_ptr_range().contains(&restored.get_ref().as_ptr()));
+            assert_ne!(restored.get_ref().as_ptr(), original.get_ref().as_ptr());
+        }
+
+        #[test]
+        fn emptied_instance_round_trips() {
+            let mut original = SelfRef::new("即将清空");
+            original.as_mut().update_data("");
+            let restored = round_trip(&original);
+            assert!(restored.is_init());
+            assert_eq!(restored.get_ref(), "");
+            assert!(restored.validate());
+        }
+
+        #[test]
+        fn uninitialized_instance_round_trips() {
+            let mut original = SelfRef::new_uninit();
+            original.as_mut().push_str("未初始化");
+            let restored = round_trip(&original);
+            assert!(!restored.is_init());
+            assert_eq!(restored.try_get_ref(), None);
+            assert_eq!(restored.data(), "未初始化");
+        }
+
+        #[test]
+        fn corrupted_offsets_are_rejected() {
+            for corrupted in [
+                r#"{"data":"前缀","offset":3,"len":6,"init":true}"#,
+                r#"{"data":"前缀","offset":1,"len":2,"init":true}"#,
+                r#"{"data":"前缀","offset":18446744073709551615,"len":2,"init":true}"#,
+            ] {
+                assert!(serde_json::from_str::<SelfRefDef>(corrupted).is_err(), "{}", corrupted);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_support::SelfRefDef;
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, string::String};
+    use core::pin::Pin;
+
+    use super::{AppendOutcome, SelfRef};
+    use crate::pin::{OptionalSelfRef, SelfRefError, WithRef};
+
+    #[test]
+    fn uninit_until_init() {
+        let mut pinned = SelfRef::new_uninit();
+        pinned.as_mut().push_str("稍后");
+        assert!(!pinned.is_init());
+        assert_eq!(pinned.try_get_ref(), None);
+        pinned.as_mut().init();
+        assert_eq!(pinned.get_ref(), "稍后");
+    }
+
+    #[test]
+    fn range_survives_push_str_and_resets_on_update() {
+        let mut pinned = SelfRef::new("key=value");
+        pinned.as_mut().set_range(4..9).unwrap();
+        pinned.as_mut().push_str(";更多");
+        // 只追加时区间保留
+        assert_eq!(pinned.get_ref(), "value");
+        assert!(core::ptr::eq(pinned.get_ref(), &pinned.data()[4..9]));
+        pinned.as_mut().update_data("k=v");
+        assert_eq!(pinned.range(), None);
+        assert_eq!(pinned.get_ref(), "k=v");
+    }
+
+    #[test]
+    fn pattern_follows_content() {
+        let mut pinned = SelfRef::new("k=v");
+        assert!(!pinned.as_mut().find_and_pin("#"));
+        assert!(pinned.as_mut().find_and_pin("="));
+        pinned.as_mut().update_data("a b");
+        assert_eq!(pinned.get_ref(), "a b");
+        pinned.as_mut().update_data("a=b");
+        assert_eq!(pinned.get_ref(), "=");
+        assert!(core::ptr::eq(pinned.get_ref(), &pinned.data()[1..2]));
+        pinned.as_mut().clear_range();
+        assert_eq!(pinned.pattern(), None);
+    }
+
+    // 在 Miri 下同时检查模式等其余字段没有泄漏
+    #[test]
+    fn into_string_releases_pattern() {
+        let mut pinned = SelfRef::new("haystack 中的 needle");
+        assert!(pinned.as_mut().find_and_pin("needle"));
+        let data = SelfRef::into_string(pinned);
+        assert_eq!(data, "haystack 中的 needle");
+
+        let mut pinned = SelfRef::new("切换 needle");
+        pinned.as_mut().find_and_pin("needle");
+        let converted: Pin<Box<OptionalSelfRef<String, WithRef>>> = pinned.into();
+        assert_eq!(converted.get_ref().map(String::as_str), Some("切换 needle"));
+    }
+
+    #[test]
+    fn str_round_trips_through_self_ref() {
+        let pinned = Pin::<Box<SelfRef>>::try_from("往返").unwrap();
+        let back: &str = pinned.get_ref();
+        assert_eq!(back, "往返");
+        assert!(core::ptr::eq(back, pinned.data()));
+    }
+
+    #[test]
+    fn empty_input_is_rejected() {
+        assert_eq!(Pin::<Box<SelfRef>>::try_from("").unwrap_err(), SelfRefError::Empty);
+        assert_eq!(Pin::<Box<SelfRef>>::try_from(String::new()).unwrap_err(), SelfRefError::Empty);
+    }
+
+    #[test]
+    fn update_in_place_reuses_buffer() {
+        let mut pinned = SelfRef::new("一条较长的初始内容");
+        let buffer = pinned.data().as_ptr();
+        pinned.as_mut().update_in_place("短");
+        // 更短的内容：缓冲区地址不变，get_ref 返回新内容
+        assert_eq!((pinned.get_ref(), pinned.get_ref().as_ptr()), ("短", buffer));
+        let capacity = pinned.capacity();
+        pinned.as_mut().update_in_place(&"长".repeat(capacity));
+        assert!(pinned.validate());
+        assert_eq!(pinned.buffer_reused_count(), if cfg!(debug_assertions) { 1 } else { 0 });
+    }
+
+    #[test]
+    fn push_str_reports_reallocation() {
+        let mut data = String::with_capacity(64);
+        data.push_str("预留");
+        let mut pinned = SelfRef::new(data);
+        let before = pinned.get_ref().as_ptr();
+        assert_eq!(pinned.as_mut().push_str("容量"), AppendOutcome::InPlace);
+        assert_eq!(pinned.get_ref().as_ptr(), before);
+        assert_eq!(pinned.as_mut().push_str(""), AppendOutcome::InPlace);
+        assert_eq!(pinned.as_mut().push_str(&"长".repeat(64)), AppendOutcome::Reallocated);
+        assert_eq!(pinned.get_ref(), pinned.data());
+        assert!(pinned.validate());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "ptr 与缓冲区不同步")]
+    fn view_detects_out_of_sync_ptr() {
+        let mut pinned = SelfRef::new("不一致");
+        // SAFETY：只在 debug 构建中通过 view 读取，view 在解引用前发现不一致并 panic
+        unsafe { pinned.as_mut().debug_retarget_ptr(0..3) };
+        let _ = pinned.view().len();
+    }
+}
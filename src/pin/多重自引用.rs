@@ -0,0 +1,232 @@
+use core::marker::PhantomPinned;
+use core::ops::Range;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use super::RangeError;
+
+/// 自引用句柄：槽位下标 + 槽位代数，见 [`MultiSelfRef`]
+///
+/// 移除自引用时槽位的代数加一，旧句柄随之失效，不会误指向之后复用该槽位的新自引用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RefId {
+    index: usize,
+    generation: u32,
+}
+
+// 槽位中的视图：整个数据，或 String / Vec<u8> 缓冲区的一个子区间
+#[derive(Debug)]
+enum View<T> {
+    Whole(NonNull<T>),
+    Range(NonNull<[u8]>),
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    view: Option<View<T>>,
+    generation: u32,
+}
+
+/// 同时持有多个自引用的容器：所有自引用都指向同一份自身拥有的数据
+///
+/// 自引用存放在 slot-map 风格的槽位中，移除其中一个不会影响其他 [`RefId`]；
+/// 数据固定后不提供修改接口，因此已有的自引用始终有效
+#[derive(Debug)]
+pub struct MultiSelfRef<T> {
+    data: Box<T>,
+    slots: Vec<Slot<T>>,
+    // 空闲槽位的下标，优先复用
+    free: Vec<usize>,
+    _pin: PhantomPinned,
+}
+
+// SAFETY：所有自引用只指向自身拥有的 Box<T>，随容器一起转移；共享时只交出 &T
+unsafe impl<T: Send> Send for MultiSelfRef<T> {}
+unsafe impl<T: Sync> Sync for MultiSelfRef<T> {}
+
+impl<T> MultiSelfRef<T> {
+    /// 创建固定在堆上、尚无自引用的实例
+    pub fn new(data: T) -> Pin<Box<Self>> {
+        Box::pin(MultiSelfRef {
+            data: Box::new(data),
+            slots: Vec::new(),
+            free: Vec::new(),
+            _pin: PhantomPinned,
+        })
+    }
+
+    /// 新增一个指向整个数据的自引用
+    pub fn add_ref(self: Pin<&mut Self>) -> RefId {
+        // 仅修改槽位，不移动实例；指针直接从 Box 取得
+        let this = unsafe { self.get_unchecked_mut() };
+        let ptr = NonNull::from(&*this.data);
+        this.insert(View::Whole(ptr))
+    }
+
+    /// 通过句柄读取整个数据；句柄已被移除或指向子区间视图时返回 `None`
+    pub fn get(&self, id: RefId) -> Option<&T> {
+        match self.view(id)? {
+            // SAFETY：ptr 指向自身的 data，固定后 data 不会被修改、移动或释放
+            View::Whole(ptr) => Some(unsafe { ptr.as_ref() }),
+            View::Range(_) => None,
+        }
+    }
+
+    /// 移除自引用，返回句柄此前是否有效；其他句柄不受影响
+    pub fn remove_ref(self: Pin<&mut Self>, id: RefId) -> bool {
+        // 只修改槽位，不移动实例
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.view(id).is_none() {
+            return false;
+        }
+        let slot = &mut this.slots[id.index];
+        slot.view = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        this.free.push(id.index);
+        true
+    }
+
+    /// 遍历所有指向整个数据的自引用
+    pub fn iter_refs(&self) -> impl Iterator<Item = (RefId, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| match slot.view {
+            // SAFETY：同 get
+            Some(View::Whole(ptr)) => Some((
+                RefId { index, generation: slot.generation },
+                unsafe { ptr.as_ref() },
+            )),
+            _ => None,
+        })
+    }
+
+    /// 当前有效的自引用数量（包括子区间视图）
+    pub fn ref_count(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// 读取完整的数据
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    // 放入空闲槽位（或新增槽位），返回对应的句柄
+    fn insert(&mut self, view: View<T>) -> RefId {
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.view = Some(view);
+                RefId { index, generation: slot.generation }
+            }
+            None => {
+                self.slots.push(Slot { view: Some(view), generation: 0 });
+                RefId { index: self.slots.len() - 1, generation: 0 }
+            }
+        }
+    }
+
+    // 代数一致时返回槽位中的视图
+    fn view(&self, id: RefId) -> Option<&View<T>> {
+        let slot = self.slots.get(id.index)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        slot.view.as_ref()
+    }
+
+    // 读取子区间视图
+    fn range_view(&self, id: RefId) -> Option<&[u8]> {
+        match self.view(id)? {
+            // SAFETY：ptr 指向自身 data 的缓冲区，固定后缓冲区不会被修改或重新分配
+            View::Range(ptr) => Some(unsafe { ptr.as_ref() }),
+            View::Whole(_) => None,
+        }
+    }
+}
+
+impl MultiSelfRef<String> {
+    /// 新增一个指向 `data[range]` 子串的自引用；越界或不在字符边界上时返回错误
+    pub fn add_range_ref(self: Pin<&mut Self>, range: Range<usize>) -> Result<RefId, RangeError> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let text = this.data.as_str();
+        for index in [range.start, range.end] {
+            if index <= text.len() && !text.is_char_boundary(index) {
+                return Err(RangeError::NotCharBoundary { index });
+            }
+        }
+        let ptr = NonNull::from(checked_range(text.as_bytes(), range)?);
+        Ok(this.insert(View::Range(ptr)))
+    }
+
+    /// 通过句柄读取子串视图；句柄已被移除或指向整个数据时返回 `None`
+    pub fn get_range(&self, id: RefId) -> Option<&str> {
+        // SAFETY：区间在 add_range_ref 中已校验过字符边界，字节来自合法的 UTF-8
+        self.range_view(id)
+            .map(|bytes| unsafe { core::str::from_utf8_unchecked(bytes) })
+    }
+}
+
+impl MultiSelfRef<Vec<u8>> {
+    /// 新增一个指向 `data[range]` 子切片的自引用；越界时返回错误
+    pub fn add_range_ref(self: Pin<&mut Self>, range: Range<usize>) -> Result<RefId, RangeError> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let ptr = NonNull::from(checked_range(&this.data, range)?);
+        Ok(this.insert(View::Range(ptr)))
+    }
+
+    /// 通过句柄读取子切片视图；句柄已被移除或指向整个数据时返回 `None`
+    pub fn get_range(&self, id: RefId) -> Option<&[u8]> {
+        self.range_view(id)
+    }
+}
+
+// 校验区间并取出对应的子切片
+fn checked_range(data: &[u8], range: Range<usize>) -> Result<&[u8], RangeError> {
+    data.get(range.clone()).ok_or(RangeError::OutOfBounds {
+        start: range.start,
+        end: range.end,
+        len: data.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use super::MultiSelfRef;
+    use crate::pin::RangeError;
+
+    #[test]
+    fn removed_handle_is_not_reused() {
+        let mut multi = MultiSelfRef::new(String::from("多个视图"));
+        let whole = multi.as_mut().add_ref();
+        let head = multi.as_mut().add_range_ref(0..3).unwrap();
+        assert_eq!(multi.ref_count(), 2);
+        assert!(core::ptr::eq(multi.get(whole).unwrap(), multi.data()));
+        assert_eq!(multi.get_range(head), Some("多"));
+        // 句柄类型不匹配时返回 None
+        assert_eq!(multi.get_range(whole), None);
+        assert_eq!(multi.get(head), None);
+
+        assert!(multi.as_mut().remove_ref(whole));
+        assert!(!multi.as_mut().remove_ref(whole));
+        let reused = multi.as_mut().add_ref();
+        // 槽位被复用，但旧句柄的代数已经失效
+        assert_ne!(reused, whole);
+        assert_eq!(multi.get(whole), None);
+        assert!(multi.get(reused).is_some());
+        assert_eq!(multi.iter_refs().map(|(id, _)| id).collect::<Vec<_>>(), [reused]);
+    }
+
+    #[test]
+    fn invalid_ranges_are_rejected() {
+        let mut text = MultiSelfRef::new(String::from("你好"));
+        assert_eq!(text.as_mut().add_range_ref(1..3), Err(RangeError::NotCharBoundary { index: 1 }));
+        assert!(matches!(text.as_mut().add_range_ref(0..9), Err(RangeError::OutOfBounds { .. })));
+        assert_eq!(text.ref_count(), 0);
+
+        let mut bytes = MultiSelfRef::new(vec![1u8, 2, 3]);
+        let tail = bytes.as_mut().add_range_ref(1..3).unwrap();
+        assert_eq!(bytes.get_range(tail), Some(&[2u8, 3][..]));
+        assert!(core::ptr::eq(bytes.get_range(tail).unwrap(), &bytes.data()[1..3]));
+    }
+}
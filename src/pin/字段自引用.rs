@@ -0,0 +1,81 @@
+use core::pin::Pin;
+use core::marker::PhantomPinned;
+use core::ptr::NonNull;
+use alloc::boxed::Box;
+
+/// 指向数据内部某一部分（字段、元素、子串）的自引用容器
+///
+/// 与 `OptionalSelfRef` 不同，自引用的目标是 `T` 的一个投影 `U`，而不是 `T` 本身
+#[derive(Debug)]
+pub struct MappedSelfRef<T, U: ?Sized> {
+    // 堆分配数据（地址固定，生命周期稳定）
+    data: Box<T>,
+    // 指向 data 内部投影的裸指针，只在固定后建立
+    self_ref: Option<NonNull<U>>,
+    _pin: PhantomPinned,
+}
+
+impl<T, U: ?Sized> MappedSelfRef<T, U> {
+    /// 创建固定的实例，并让自引用指向 `f(&data)` 返回的投影
+    ///
+    /// `f` 的签名是 `for<'a> FnOnce(&'a T) -> &'a U`：返回值的生命周期必须来自参数，
+    /// 闭包无法把指向局部变量的引用偷渡出来；唯一的例外是 `'static` 引用，它永远有效
+    ///
+    /// ```
+    /// use rust_advanced::pin::MappedSelfRef;
+    ///
+    /// let pair = MappedSelfRef::new_with_ref_map((1, String::from("元素")), |p| p.1.as_str());
+    /// assert_eq!(pair.get_ref(), Some("元素"));
+    /// assert!(core::ptr::eq(pair.get_ref().unwrap(), pair.data().1.as_str()));
+    /// ```
+    ///
+    /// 返回指向局部变量的引用无法通过编译：
+    ///
+    /// ```compile_fail
+    /// use rust_advanced::pin::MappedSelfRef;
+    ///
+    /// let local = String::from("局部变量");
+    /// // 签名要求返回值对任意 'a 成立，借用 local 的 &str 活得不够长
+    /// let _bad = MappedSelfRef::new_with_ref_map(0, |_| local.as_str());
+    /// ```
+    ///
+    /// 闭包内部创建的临时值同样不行：
+    ///
+    /// ```compile_fail
+    /// use rust_advanced::pin::MappedSelfRef;
+    ///
+    /// let _bad = MappedSelfRef::new_with_ref_map(0, |_| {
+    ///     let owned = String::from("闭包内的局部变量");
+    ///     owned.as_str()
+    /// });
+    /// ```
+    pub fn new_with_ref_map<F>(data: T, f: F) -> Pin<Box<Self>>
+    where
+        F: for<'a> FnOnce(&'a T) -> &'a U,
+    {
+        let mut pinned = Box::pin(MappedSelfRef {
+            data: Box::new(data),
+            self_ref: None,
+            _pin: PhantomPinned,
+        });
+
+        unsafe {
+            // 仅修改字段，不移动实例
+            let this = pinned.as_mut().get_unchecked_mut();
+            this.self_ref = Some(NonNull::from(f(&*this.data)));
+        }
+
+        pinned
+    }
+
+    /// 读取自引用指向的投影
+    pub fn get_ref(&self) -> Option<&U> {
+        // SAFETY：投影来自自身拥有的 Box<T>（或 'static 数据），实例固定后 data 不再被修改或移动
+        self.self_ref.map(|ptr| unsafe { ptr.as_ref() })
+    }
+
+    /// 读取完整的数据
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+}
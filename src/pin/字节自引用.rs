@@ -0,0 +1,184 @@
+use core::pin::Pin;
+use core::marker::PhantomPinned;
+use core::ops::Range;
+use core::ptr::NonNull;
+
+use alloc::{boxed::Box, vec::Vec};
+use super::{InvariantViolation, RangeError};
+
+/// 自引用字节缓冲区：[`SelfRef`](super::SelfRef) 的二进制版本，`ptr` 指向自身 `data`
+/// （或用 [`set_range`](Self::set_range) 选定的子区间），必须通过 `Pin<Box<SelfRefBytes>>` 使用
+///
+/// 与 `SelfRef` 不同，内容可以为空，区间也没有字符边界的限制
+#[derive(Debug)]
+pub struct SelfRefBytes {
+    data: Vec<u8>,
+    ptr: NonNull<[u8]>,
+    // ptr 指向的子区间，None 表示整个缓冲区
+    range: Option<Range<usize>>,
+    _pin: PhantomPinned,
+}
+
+// SAFETY：同 SelfRef，ptr 只指向自身 data 的堆缓冲区，随实例一起转移；
+// 刻意不实现 Sync，保持 NonNull 带来的 !Sync
+unsafe impl Send for SelfRefBytes {}
+
+impl SelfRefBytes {
+    /// 创建固定在堆上的实例：`&[u8]` 会被复制，已有的 `Vec<u8>` 直接移入
+    pub fn new(bytes: impl Into<Vec<u8>>) -> Pin<Box<SelfRefBytes>> {
+        let mut pinned = Box::pin(SelfRefBytes {
+            data: bytes.into(),
+            ptr: NonNull::from(&[][..]),
+            range: None,
+            _pin: PhantomPinned,
+        });
+        // 仅修改字段，不移动实例；ptr 在固定之后才从缓冲区推导
+        unsafe { pinned.as_mut().get_unchecked_mut() }.sync_ptr();
+        pinned
+    }
+
+    /// 通过自引用读取字节，设置了区间时只返回对应的子区间
+    pub fn get_ref(&self) -> &[u8] {
+        debug_assert!(self.validate(), "ptr 与 data 的缓冲区不一致");
+        // SAFETY：ptr 始终指向自身 data 的缓冲区，每次修改 data 后都会重新同步
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// 引用的字节恰好是合法的 UTF-8 时以 `&str` 读取，否则返回 `None`
+    pub fn as_str_checked(&self) -> Option<&str> {
+        core::str::from_utf8(self.get_ref()).ok()
+    }
+
+    /// 直接读取 `data`（不经过自引用指针）
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 缓冲区的字节长度
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// 缓冲区是否为空
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// 替换内容并同步自引用指针；区间在新内容上越界时恢复为整个缓冲区
+    pub fn update_data(mut self: Pin<&mut SelfRefBytes>, new_content: &[u8]) {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        this.data = new_content.to_vec();
+        if this.range.as_ref().is_some_and(|range| this.data.get(range.clone()).is_none()) {
+            this.range = None;
+        }
+        this.sync_ptr();
+        debug_verify!(self);
+    }
+
+    /// 只固定一段子区间：让 ptr 指向 `data[range]`；区间越界时返回错误，原有的区间保持不变
+    pub fn set_range(mut self: Pin<&mut SelfRefBytes>, range: Range<usize>) -> Result<(), RangeError> {
+        if self.data.get(range.clone()).is_none() {
+            return Err(RangeError::OutOfBounds {
+                start: range.start,
+                end: range.end,
+                len: self.data.len(),
+            });
+        }
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        this.range = Some(range);
+        this.sync_ptr();
+        debug_verify!(self);
+        Ok(())
+    }
+
+    /// 取消区间，ptr 重新指向整个缓冲区
+    pub fn clear_range(mut self: Pin<&mut SelfRefBytes>) {
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        this.range = None;
+        this.sync_ptr();
+        debug_verify!(self);
+    }
+
+    /// 当前选定的子区间，`None` 表示整个缓冲区
+    pub fn range(&self) -> Option<Range<usize>> {
+        self.range.clone()
+    }
+
+    /// 检查 ptr 是否仍与 data 的缓冲区一致（地址与长度都相同）
+    pub fn validate(&self) -> bool {
+        self.verify_invariant().is_ok()
+    }
+
+    /// 校验不变量：ptr 的起始地址与长度都必须与当前缓冲区（设置了区间时为对应的子区间）一致
+    pub fn verify_invariant(&self) -> Result<(), InvariantViolation> {
+        let target = self.target();
+        let expected = target.as_ptr() as usize;
+        let actual = self.ptr.as_ptr() as *const u8 as usize;
+        if actual != expected {
+            return Err(InvariantViolation::AddressMismatch { expected, actual });
+        }
+        // 只读取胖指针中的长度元数据，不解引用
+        let len = self.ptr.as_ptr().len();
+        if len != target.len() {
+            return Err(InvariantViolation::LengthMismatch {
+                expected: target.len(),
+                actual: len,
+            });
+        }
+        Ok(())
+    }
+
+    // 从当前缓冲区重新推导 ptr
+    fn sync_ptr(&mut self) {
+        self.ptr = NonNull::from(self.target());
+    }
+
+    // ptr 应当指向的内容：选定的子区间，或整个缓冲区
+    fn target(&self) -> &[u8] {
+        match &self.range {
+            Some(range) => &self.data[range.clone()],
+            None => &self.data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::SelfRefBytes;
+    use crate::pin::RangeError;
+
+    #[test]
+    fn range_selects_sub_slice() {
+        let mut bytes = SelfRefBytes::new(vec![0xff, b'o', b'k', 0]);
+        assert_eq!(bytes.get_ref(), &[0xff, b'o', b'k', 0]);
+        assert_eq!(bytes.as_str_checked(), None);
+        bytes.as_mut().set_range(1..3).unwrap();
+        assert_eq!(bytes.get_ref(), b"ok");
+        assert!(core::ptr::eq(bytes.get_ref(), &bytes.data()[1..3]));
+        assert_eq!(bytes.as_str_checked(), Some("ok"));
+        assert_eq!(
+            bytes.as_mut().set_range(2..9),
+            Err(RangeError::OutOfBounds { start: 2, end: 9, len: 4 })
+        );
+        assert_eq!(bytes.range(), Some(1..3));
+        bytes.as_mut().clear_range();
+        assert_eq!(bytes.get_ref().len(), 4);
+        assert!(bytes.verify_invariant().is_ok());
+    }
+
+    #[test]
+    fn update_data_resets_out_of_range_selection() {
+        let mut bytes = SelfRefBytes::new(&b"abcdef"[..]);
+        bytes.as_mut().set_range(4..6).unwrap();
+        bytes.as_mut().update_data(b"xyz");
+        assert_eq!(bytes.range(), None);
+        assert_eq!(bytes.get_ref(), b"xyz");
+        bytes.as_mut().set_range(0..1).unwrap();
+        bytes.as_mut().update_data(b"");
+        assert!(bytes.is_empty());
+        assert_eq!(bytes.get_ref(), b"");
+        assert!(bytes.validate());
+    }
+}
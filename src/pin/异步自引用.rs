@@ -0,0 +1,74 @@
+use core::future::Future;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::task::{Context, Poll};
+use alloc::string::{String, ToString};
+
+/// 手写的自引用 Future：模拟 `async fn` 状态机跨 `.await` 持有对自身数据的借用
+///
+/// 相当于:
+/// ```text
+/// async fn measure(data: String) -> usize {
+///     let view: &str = &data;  // 借用自身的数据
+///     yield_now().await;       // 借用跨越挂起点
+///     view.len()
+/// }
+/// ```
+/// 第一次 poll 建立 `view` 并返回 `Pending`，之后的 poll 通过 `view` 计算长度并返回 `Ready`
+#[derive(Debug)]
+pub struct SelfRefFuture {
+    data: String,
+    // 跨挂起点保留的借用，只在第一次 poll（已固定）时建立
+    view: Option<NonNull<str>>,
+    _pin: PhantomPinned,
+}
+
+impl SelfRefFuture {
+    /// 创建尚未开始执行的 Future，和 `async fn` 的返回值一样在固定之前可以自由移动
+    pub fn new(s: &str) -> Self {
+        SelfRefFuture {
+            data: s.to_string(),
+            view: None,
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+impl Future for SelfRefFuture {
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+        // 与 SelfRef::update_data 相同：仅修改字段，不移动实例
+        let this = unsafe { self.get_unchecked_mut() };
+        match this.view {
+            None => {
+                // 挂起前建立借用，并请求再次被 poll
+                this.view = Some(NonNull::from(this.data.as_str()));
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            // SAFETY：view 指向自身 data 的缓冲区，data 在 Future 存续期间不再被修改
+            Some(view) => Poll::Ready(unsafe { view.as_ref() }.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+    use core::future::Future;
+    use core::task::{Context, Poll, Waker};
+
+    use super::SelfRefFuture;
+
+    #[test]
+    fn pending_then_ready_with_len() {
+        // 固定之前可以自由移动
+        let future = SelfRefFuture::new("跨挂起点");
+        let mut future = Box::pin(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready("跨挂起点".len()));
+    }
+}
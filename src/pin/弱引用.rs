@@ -0,0 +1,90 @@
+use core::fmt;
+use core::pin::Pin;
+use alloc::sync::{Arc, Weak};
+
+use super::{OptionalSelfRef, WithRef};
+
+/// 指向 `Pin<Arc<OptionalSelfRef<T, WithRef>>>` 的弱句柄
+///
+/// 观察者不拥有容器：拥有者全部释放后 [`upgrade`](Self::upgrade) 返回 `None`，
+/// 而不是留下一个悬垂的自引用。只能由 [`OptionalSelfRef::downgrade`] 从固定的 Arc 得到，
+/// 因此升级出来的 Arc 一定指向固定的实例
+pub struct PinWeak<T: ?Sized> {
+    inner: Weak<OptionalSelfRef<T, WithRef>>,
+}
+
+impl<T: ?Sized> PinWeak<T> {
+    /// 尝试升级为固定的强引用，拥有者已全部释放时返回 `None`
+    pub fn upgrade(&self) -> Option<Pin<Arc<OptionalSelfRef<T, WithRef>>>> {
+        // SAFETY：inner 来自固定的 Arc，实例从未被移动，重新固定不会破坏 Pin 的约定
+        self.inner
+            .upgrade()
+            .map(|arc| unsafe { Pin::new_unchecked(arc) })
+    }
+}
+
+impl<T> OptionalSelfRef<T, WithRef> {
+    /// 创建放在 Arc 中并已建立自引用的实例，可通过 [`downgrade`](Self::downgrade) 分发弱句柄
+    pub fn new_arc_with_ref(data: T) -> Pin<Arc<Self>> {
+        let mut arc = Arc::new(OptionalSelfRef::new_no_ref(data).into_with_ref());
+        // 共享之前引用计数为 1，可以取得唯一的可变引用来建立自引用
+        let inner = Arc::get_mut(&mut arc).expect("尚未共享");
+        // SAFETY：实例已经位于 Arc 的堆分配中，此后只通过 Pin<Arc<_>> 访问，不会再被移动
+        unsafe { Pin::new_unchecked(inner) }.init_self_ref();
+        unsafe { Pin::new_unchecked(arc) }
+    }
+}
+
+impl<T: ?Sized> OptionalSelfRef<T, WithRef> {
+    /// 从固定的 Arc 得到弱句柄，不增加强引用计数
+    pub fn downgrade(this: &Pin<Arc<Self>>) -> PinWeak<T> {
+        // SAFETY：取出的 Arc 只用来生成 Weak，随即释放，实例本身没有被移动
+        let arc = unsafe { Pin::into_inner_unchecked(Pin::clone(this)) };
+        PinWeak {
+            inner: Arc::downgrade(&arc),
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for PinWeak<T> {
+    fn clone(&self) -> Self {
+        PinWeak {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for PinWeak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinWeak")
+            .field("alive", &(self.inner.strong_count() > 0))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use crate::pin::OptionalSelfRef;
+
+    #[test]
+    fn upgrade_sees_same_pinned_instance() {
+        let owner = OptionalSelfRef::new_arc_with_ref(String::from("弱引用"));
+        let weak = OptionalSelfRef::downgrade(&owner);
+        let upgraded = weak.upgrade().unwrap();
+        assert!(core::ptr::eq(&*upgraded, &*owner));
+        assert_eq!(upgraded.get_ref().map(String::as_str), Some("弱引用"));
+        assert!(upgraded.is_ref_valid());
+    }
+
+    #[test]
+    fn upgrade_fails_after_owners_dropped() {
+        let owner = OptionalSelfRef::new_arc_with_ref(7);
+        let weak = OptionalSelfRef::downgrade(&owner);
+        let another = weak.clone();
+        drop(owner);
+        assert!(weak.upgrade().is_none());
+        assert!(another.upgrade().is_none());
+    }
+}
@@ -0,0 +1,83 @@
+use core::cell::Cell;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use alloc::boxed::Box;
+
+/// 首次访问时才建立自引用的容器，只需要 `Pin<&Self>`
+///
+/// 自引用存放在 `Cell` 中，通过共享引用即可写入；`Cell` 使类型保持 `!Sync`，
+/// 不能跨线程共享 `&OnceSelfRef`：
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<rust_advanced::pin::OnceSelfRef<i32>>();
+/// ```
+#[derive(Debug)]
+pub struct OnceSelfRef<T> {
+    data: Box<T>,
+    // 惰性建立的自引用，None 表示尚未访问过
+    self_ref: Cell<Option<NonNull<T>>>,
+    _pin: PhantomPinned,
+}
+
+// SAFETY：self_ref 只指向自身拥有的 Box<T>，随实例一起转移；
+// 只实现 Send，`Cell` 带来的 !Sync 保持不变
+unsafe impl<T: Send> Send for OnceSelfRef<T> {}
+
+impl<T> OnceSelfRef<T> {
+    /// 创建固定在堆上的实例，此时尚未建立自引用
+    pub fn new(data: T) -> Pin<Box<Self>> {
+        Box::pin(OnceSelfRef {
+            data: Box::new(data),
+            self_ref: Cell::new(None),
+            _pin: PhantomPinned,
+        })
+    }
+
+    /// 读取自引用，首次调用时建立
+    pub fn get_or_init_ref(self: Pin<&Self>) -> &T {
+        let this = self.get_ref();
+        let ptr = match this.self_ref.get() {
+            Some(ptr) => ptr,
+            None => {
+                // 写入只发生在 Cell 内部，不会移动实例，也不需要 &mut；
+                // 指针指向自身拥有的 Box<T>，实例已固定，Box 不会被替换
+                let ptr = NonNull::from(&*this.data);
+                this.self_ref.set(Some(ptr));
+                ptr
+            }
+        };
+        // SAFETY：ptr 指向 self.data，data 在实例存活期间不会被修改或释放，
+        // 返回值的生命周期与 Pin<&Self> 绑定
+        unsafe { ptr.as_ref() }
+    }
+
+    /// 自引用是否已经建立
+    pub fn is_initialized(&self) -> bool {
+        self.self_ref.get().is_some()
+    }
+
+    /// 读取完整的数据
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::OnceSelfRef;
+
+    #[test]
+    fn ref_is_built_on_first_access() {
+        let once = OnceSelfRef::new(String::from("惰性"));
+        assert!(!once.is_initialized());
+        let first = once.as_ref().get_or_init_ref();
+        assert!(core::ptr::eq(first, once.data()));
+        assert!(once.is_initialized());
+        // 之后的访问复用同一个自引用
+        assert!(core::ptr::eq(once.as_ref().get_or_init_ref(), first));
+    }
+}
@@ -0,0 +1,151 @@
+use core::ops::Deref;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use alloc::boxed::Box;
+
+use pin_project::pin_project;
+
+/// 借助 `pin-project` 实现结构化固定的自引用容器（需要 `pin-project` feature）
+///
+/// data 放在独立的堆分配中，以 `Pin<Box<T>>` 持有：投影只交出 `Pin<&mut T>`。
+/// 若持有 `Box<T>` 并投影出 `Pin<&mut Box<T>>`，由于 `Box<T>: Unpin`，
+/// 调用方可以经由 `&mut Box<T>` 把已经 poll 过的 `!Unpin` 数据移走，结构化固定就失去意义。
+/// self_ref 不参与结构化固定，字段访问全部由 pin-project 生成，无需手写 `get_unchecked_mut`
+#[pin_project(!Unpin)]
+#[derive(Debug)]
+pub struct ProjectedSelfRef<T> {
+    // 结构化固定：只会以 Pin<&mut T> 的形式交出
+    data: Pin<Box<T>>,
+    // 非结构化字段：指向自身的 data，投影期间为 None
+    self_ref: Option<NonNull<T>>,
+}
+
+/// [`ProjectedSelfRef::project_data`] 的返回值：通过 [`data`](Self::data) 取得结构化固定的数据，
+/// drop 时重新建立自引用
+///
+/// 数据字段是私有的，持有的是容器自己的 `Pin<Box<T>>`，drop 时也从它推导指针：
+/// 调用方只能拿到 `Pin<&mut T>`，无法把投影换成指向别处的引用，自引用因此不会悬垂
+///
+/// ```compile_fail
+/// use rust_advanced::pin::ProjectedSelfRef;
+///
+/// let mut outer = ProjectedSelfRef::new_with_ref(String::from("容器"));
+/// let mut other = Box::pin(String::from("别处"));
+/// let mut projection = outer.as_mut().project_data();
+/// projection.data = other.as_mut();
+/// ```
+#[derive(Debug)]
+pub struct SelfRefProjection<'a, T> {
+    data: &'a mut Pin<Box<T>>,
+    self_ref: &'a mut Option<NonNull<T>>,
+}
+
+impl<T> SelfRefProjection<'_, T> {
+    /// 固定的数据，可以直接 poll 内部的 `!Unpin` Future
+    pub fn data(&mut self) -> Pin<&mut T> {
+        self.data.as_mut()
+    }
+}
+
+impl<T> Deref for SelfRefProjection<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.data
+    }
+}
+
+impl<T> Drop for SelfRefProjection<'_, T> {
+    fn drop(&mut self) {
+        // 从容器拥有的 Box 推导，而不是从交出去的引用
+        *self.self_ref = Some(NonNull::from(&**self.data));
+    }
+}
+
+impl<T> ProjectedSelfRef<T> {
+    /// 创建固定在堆上的实例，并建立自引用
+    pub fn new_with_ref(data: T) -> Pin<Box<Self>> {
+        let mut pinned = Box::pin(ProjectedSelfRef {
+            data: Box::pin(data),
+            self_ref: None,
+        });
+        // 投影结束时建立自引用
+        pinned.as_mut().project_data();
+        pinned
+    }
+
+    /// 通过自引用读取数据；投影的返回值被 `mem::forget` 时为 `None`，直到下一次投影结束
+    pub fn get_ref(&self) -> Option<&T> {
+        // SAFETY：self_ref 只由 SelfRefProjection 的 drop 建立，之后再访问 data 都要经过投影，
+        // 而投影会先清空 self_ref，因此非 None 时一定指向当前的 data
+        self.self_ref.map(|ptr| unsafe { ptr.as_ref() })
+    }
+
+    /// 公开的结构化投影：以 `Pin<&mut T>` 交出数据，返回值 drop 时自动同步自引用
+    ///
+    /// pin-project 生成的 `project()` 对 pub 类型总是降级为 `pub(crate)`，且方法名不可更改，
+    /// 因此由本方法在其基础上对外提供投影
+    pub fn project_data(self: Pin<&mut Self>) -> SelfRefProjection<'_, T> {
+        let this = self.project();
+        // 先清空：即使返回值被遗忘，get_ref 也不会读到投影期间失效的指针
+        *this.self_ref = None;
+        SelfRefProjection {
+            data: this.data,
+            self_ref: this.self_ref,
+        }
+    }
+
+    /// 以 `Pin<&mut T>` 访问数据（例如 poll 内部的 Future），结束后自动同步自引用
+    pub fn with_data_mut<R>(self: Pin<&mut Self>, f: impl FnOnce(Pin<&mut T>) -> R) -> R {
+        f(self.project_data().data())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::future::Future;
+    use core::task::{Context, Poll, Waker};
+
+    use super::ProjectedSelfRef;
+    use crate::pin::SelfRefFuture;
+
+    #[test]
+    fn project_data_polls_inner_future_to_completion() {
+        let mut wrapped = ProjectedSelfRef::new_with_ref(SelfRefFuture::new("投影后 poll"));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut polls = 0;
+        let len = loop {
+            polls += 1;
+            if let Poll::Ready(len) = wrapped.as_mut().project_data().data().poll(&mut cx) {
+                break len;
+            }
+        };
+        assert_eq!(len, "投影后 poll".len());
+        assert_eq!(polls, 2);
+        assert!(wrapped.get_ref().is_some());
+    }
+
+    #[test]
+    fn self_ref_resyncs_after_projection() {
+        let mut values = ProjectedSelfRef::new_with_ref(vec![1, 2, 3]);
+        let before = values.get_ref().unwrap() as *const Vec<i32>;
+        values.as_mut().with_data_mut(|mut v| v.push(4));
+        {
+            let mut projection = values.as_mut().project_data();
+            projection.data().set(vec![9]);
+            assert_eq!(*projection, [9]);
+        }
+        assert_eq!(values.get_ref().unwrap(), &[9]);
+        // 数据在独立的堆分配中，投影不会改变其地址
+        assert!(core::ptr::eq(values.get_ref().unwrap(), before));
+    }
+
+    #[test]
+    fn forgotten_projection_clears_self_ref() {
+        let mut values = ProjectedSelfRef::new_with_ref(String::from("遗忘"));
+        core::mem::forget(values.as_mut().project_data());
+        assert!(values.get_ref().is_none());
+        drop(values.as_mut().project_data());
+        assert_eq!(values.get_ref().unwrap(), "遗忘");
+    }
+}
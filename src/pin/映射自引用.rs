@@ -0,0 +1,121 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use std::collections::HashMap;
+
+/// 带「热点」自引用的映射：`cached` 指向固定的 HashMap 中最近访问的值（需要 `std` feature）
+///
+/// 插入可能触发扩容重哈希，把所有值搬到新的表中，因此 `insert` / `remove` 都会先清除缓存，
+/// 之后必须重新 [`touch`](Self::touch) 才能得到热点
+#[derive(Debug)]
+pub struct SelfRefMap<K, V> {
+    // HashMap 的表本身就在堆上，移动 HashMap 不会搬动其中的值，无需再套一层 Box
+    map: HashMap<K, V>,
+    // 指向 map 中某个值的裸指针，只在没有修改 map 的期间有效
+    cached: Option<NonNull<V>>,
+    _pin: PhantomPinned,
+}
+
+// SAFETY：cached 只指向自身拥有的 HashMap 中的值，随容器一起转移；共享时只交出 &V
+unsafe impl<K: Send, V: Send> Send for SelfRefMap<K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for SelfRefMap<K, V> {}
+
+impl<K: Eq + Hash, V> SelfRefMap<K, V> {
+    /// 创建固定在堆上的空映射
+    pub fn new() -> Pin<Box<Self>> {
+        Box::pin(SelfRefMap {
+            map: HashMap::new(),
+            cached: None,
+            _pin: PhantomPinned,
+        })
+    }
+
+    /// 访问 `key` 并把它设为热点，键不存在时清除热点
+    pub fn touch<Q>(self: Pin<&mut Self>, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        // 仅修改 cached，不移动实例
+        let this = unsafe { self.get_unchecked_mut() };
+        this.cached = this.map.get(key).map(NonNull::from);
+        this.get_cached()
+    }
+
+    /// 读取热点值
+    pub fn get_cached(&self) -> Option<&V> {
+        // SAFETY：cached 在最近一次 touch 时从 map 中取得，之后任何修改 map 的操作都会先清除它
+        self.cached.map(|ptr| unsafe { ptr.as_ref() })
+    }
+
+    /// 插入键值对：可能触发重哈希，因此先清除热点
+    pub fn insert(self: Pin<&mut Self>, key: K, value: V) -> Option<V> {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.cached = None;
+        this.map.insert(key, value)
+    }
+
+    /// 移除键值对：被移除的可能正是热点，因此先清除热点
+    pub fn remove<Q>(self: Pin<&mut Self>, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.cached = None;
+        this.map.remove(key)
+    }
+
+    /// 按键读取，不影响热点
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key)
+    }
+
+    /// 键值对数量
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SelfRefMap;
+
+    #[test]
+    fn touch_caches_value_in_place() {
+        let mut map = SelfRefMap::new();
+        map.as_mut().insert("a", 1);
+        map.as_mut().insert("b", 2);
+        assert_eq!(map.as_mut().touch("b"), Some(&2));
+        assert!(core::ptr::eq(map.get_cached().unwrap(), map.get("b").unwrap()));
+        assert_eq!(map.as_mut().touch("missing"), None);
+        assert_eq!(map.get_cached(), None);
+    }
+
+    #[test]
+    fn mutations_clear_cache() {
+        let mut map = SelfRefMap::new();
+        map.as_mut().insert(1, "一");
+        map.as_mut().touch(&1);
+        // 插入可能触发重哈希，热点随之清除
+        for i in 2..64 {
+            map.as_mut().insert(i, "多");
+        }
+        assert_eq!(map.get_cached(), None);
+        map.as_mut().touch(&1);
+        assert_eq!(map.as_mut().remove(&1), Some("一"));
+        assert_eq!(map.get_cached(), None);
+        assert_eq!(map.len(), 62);
+    }
+}
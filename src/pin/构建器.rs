@@ -0,0 +1,158 @@
+use core::pin::Pin;
+
+use alloc::{boxed::Box, string::String};
+use super::{BuildError, OptionalSelfRef, SelfRef, SelfRefError, WithRef};
+
+/// 分步配置 `OptionalSelfRef`，最后按是否需要自引用选择终结方法
+///
+/// - [`build_movable`](Self::build_movable)：得到可移动的 NoRef 实例，要求未请求自引用；
+/// - [`build_pinned`](Self::build_pinned)：得到固定的 WithRef 实例，按配置决定是否建立自引用。
+///
+/// 配置不合法时返回 [`BuildError`]，不会 panic
+#[derive(Debug)]
+pub struct OptionalSelfRefBuilder<T> {
+    data: T,
+    self_ref: bool,
+    display_name: Option<String>,
+}
+
+impl<T> OptionalSelfRefBuilder<T> {
+    /// 以数据开始构建，默认不建立自引用、没有显示名称
+    pub fn new(data: T) -> Self {
+        OptionalSelfRefBuilder {
+            data,
+            self_ref: false,
+            display_name: None,
+        }
+    }
+
+    /// 是否在固定后建立自引用
+    pub fn with_self_ref(mut self, self_ref: bool) -> Self {
+        self.self_ref = self_ref;
+        self
+    }
+
+    /// 设置显示名称，Display 输出时作为前缀
+    pub fn with_display_name(mut self, name: impl Into<String>) -> Self {
+        self.display_name = Some(name.into());
+        self
+    }
+
+    /// 构建可移动的无自引用实例
+    pub fn build_movable(self) -> Result<OptionalSelfRef<T>, BuildError> {
+        if self.self_ref {
+            return Err(BuildError::SelfRefNeedsPinning);
+        }
+        let name = checked_name(self.display_name)?;
+        let mut built = OptionalSelfRef::new_no_ref(self.data);
+        built.set_display_name(name);
+        Ok(built)
+    }
+
+    /// 构建固定的实例：请求了自引用时在固定后建立
+    pub fn build_pinned(self) -> Result<Pin<Box<OptionalSelfRef<T, WithRef>>>, BuildError> {
+        let name = checked_name(self.display_name)?;
+        let mut unpinned = OptionalSelfRef::new_no_ref(self.data);
+        unpinned.set_display_name(name);
+        let mut pinned = Box::pin(unpinned.into_with_ref());
+        if self.self_ref {
+            pinned.as_mut().init_self_ref();
+        }
+        Ok(pinned)
+    }
+}
+
+// 校验显示名称
+fn checked_name(name: Option<String>) -> Result<Option<Box<str>>, BuildError> {
+    match name {
+        Some(name) if name.is_empty() => Err(BuildError::EmptyDisplayName),
+        name => Ok(name.map(String::into_boxed_str)),
+    }
+}
+
+/// 分步构造 `SelfRef`：先收集内容与容量，`build` 时才固定并推导指针
+///
+/// 指针只会从最终固定的实例中的缓冲区推导，构造过程中的中间 String 不会留下任何指针
+#[derive(Debug, Default)]
+pub struct SelfRefBuilder {
+    data: String,
+    capacity: usize,
+}
+
+impl SelfRefBuilder {
+    /// 创建空的构建器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置内容
+    pub fn data(mut self, data: impl Into<String>) -> Self {
+        self.data = data.into();
+        self
+    }
+
+    /// 预留缓冲区容量（字节），之后总长度不超过它的 `push_str` 不会重新分配
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// 构造固定的实例
+    ///
+    /// # Panics
+    /// 内容为空时 panic，与 [`SelfRef::new`] 一致；需要处理错误时用 [`try_build`](Self::try_build)
+    pub fn build(self) -> Pin<Box<SelfRef>> {
+        match self.try_build() {
+            Ok(pinned) => pinned,
+            Err(err) => panic!("SelfRefBuilder::build 失败：{}", err),
+        }
+    }
+
+    /// 构造固定的实例，内容为空时返回错误
+    pub fn try_build(self) -> Result<Pin<Box<SelfRef>>, SelfRefError> {
+        if self.data.is_empty() {
+            return Err(SelfRefError::Empty);
+        }
+        let mut data = self.data;
+        data.reserve(self.capacity.saturating_sub(data.len()));
+        Ok(SelfRef::from_string(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::{OptionalSelfRefBuilder, SelfRefBuilder};
+    use crate::pin::{BuildError, SelfRefError};
+
+    #[test]
+    fn optional_builder_validates_config() {
+        let movable = OptionalSelfRefBuilder::new(1).with_display_name("计数").build_movable().unwrap();
+        assert_eq!(movable.get_ref(), None);
+        assert!(movable.to_string().starts_with("[计数] "));
+
+        let err = OptionalSelfRefBuilder::new(1).with_self_ref(true).build_movable().unwrap_err();
+        assert_eq!(err, BuildError::SelfRefNeedsPinning);
+        let err = OptionalSelfRefBuilder::new(1).with_display_name("").build_pinned().unwrap_err();
+        assert_eq!(err, BuildError::EmptyDisplayName);
+    }
+
+    #[test]
+    fn optional_builder_pins_with_or_without_ref() {
+        let pinned = OptionalSelfRefBuilder::new(2).with_self_ref(true).build_pinned().unwrap();
+        assert_eq!(pinned.get_ref(), Some(&2));
+        assert!(pinned.is_ref_valid());
+        let pinned = OptionalSelfRefBuilder::new(3).build_pinned().unwrap();
+        assert_eq!(pinned.get_ref(), None);
+    }
+
+    #[test]
+    fn self_ref_builder_reserves_capacity() {
+        let built = SelfRefBuilder::new().data("预留").with_capacity(64).build();
+        assert!(built.capacity() >= 64);
+        assert_eq!(built.get_ref(), "预留");
+        assert!(built.validate());
+        assert_eq!(SelfRefBuilder::new().try_build().unwrap_err(), SelfRefError::Empty);
+    }
+}
@@ -0,0 +1,88 @@
+use core::pin::Pin;
+use core::marker::PhantomPinned;
+use core::ptr::{self, NonNull};
+use alloc::boxed::Box;
+
+/// 泛型自引用容器：`ptr` 指向自身 Box 中的整个 `T`
+///
+/// 与 [`SelfRef`](super::SelfRef) 的区别：SelfRef 的 `ptr` 是 `*const str`，指向 String
+/// 在堆上的字节内容（胖指针，随缓冲区重新分配而变化）；这里的 `ptr` 是 `*const T`，
+/// 指向值本身。两者指向的对象不同，SelfRef 写成 `SelfRefGeneric<String>` 的别名会改变
+/// `get_ref` 的返回类型与语义，因此 String 版本保持特化，泛型版本单独提供
+#[derive(Debug)]
+pub struct SelfRefGeneric<T> {
+    data: Box<T>,
+    ptr: NonNull<T>,
+    _pin: PhantomPinned,
+}
+
+impl<T> SelfRefGeneric<T> {
+    /// 创建固定在堆上的实例：先固定，再让 ptr 指向 Box 中的数据
+    pub fn new(data: T) -> Pin<Box<Self>> {
+        let mut pinned = Box::pin(SelfRefGeneric {
+            data: Box::new(data),
+            // 占位，固定后立即同步
+            ptr: NonNull::dangling(),
+            _pin: PhantomPinned,
+        });
+        pinned.as_mut().sync_ptr();
+        pinned
+    }
+
+    /// 通过自引用指针读取数据
+    pub fn get_ref(&self) -> &T {
+        // SAFETY：ptr 在 new 中固定后立即指向自身的 Box，Box 在实例存续期间不会被替换
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// 原地替换数据并同步指针：新值写入已有的 Box，旧值在原处析构
+    pub fn update(mut self: Pin<&mut Self>, data: T) {
+        // 仅改写 Box 中的值，不移动实例，也不移动 Box
+        *unsafe { self.as_mut().get_unchecked_mut() }.data = data;
+        self.sync_ptr();
+    }
+
+    // 从 Box 直接取裸指针，不经过中间引用
+    fn sync_ptr(self: Pin<&mut Self>) {
+        let this = unsafe { self.get_unchecked_mut() };
+        // SAFETY：Box 的指针永远非空
+        this.ptr = unsafe { NonNull::new_unchecked(ptr::addr_of_mut!(*this.data)) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{rc::Rc, vec, vec::Vec};
+    use core::cell::Cell;
+
+    use super::SelfRefGeneric;
+
+    // 析构时累加共享的计数
+    struct Tracked(Rc<Cell<usize>>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn update_replaces_value_in_same_box() {
+        let mut values = SelfRefGeneric::new(vec![1, 2]);
+        let before = values.get_ref() as *const Vec<i32>;
+        values.as_mut().update(vec![3, 4, 5]);
+        assert_eq!(values.get_ref(), &[3, 4, 5]);
+        // 新值写入已有的 Box，地址不变
+        assert!(core::ptr::eq(values.get_ref(), before));
+    }
+
+    #[test]
+    fn update_drops_old_value() {
+        let drops = Rc::new(Cell::new(0));
+        let mut tracked = SelfRefGeneric::new(Tracked(drops.clone()));
+        tracked.as_mut().update(Tracked(drops.clone()));
+        assert_eq!(drops.get(), 1);
+        drop(tracked);
+        assert_eq!(drops.get(), 2);
+    }
+}
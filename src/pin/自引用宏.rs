@@ -0,0 +1,115 @@
+/// 生成自引用结构体：`selfref!(MyBuf, Vec<u8>)`
+///
+/// 生成的类型把数据内联存放，`ptr` 指向自身的 `data` 字段（真正依赖结构体地址），
+/// 自动插入 `PhantomPinned`，并提供：
+/// - `new(data) -> Pin<Box<Self>>`：先固定、再建立自引用；
+/// - `get_ref(&self)`：通过自引用读取数据；
+/// - `update(self: Pin<&mut Self>, data)`：替换数据并同步自引用。
+///
+/// `get_unchecked_mut` 只出现在生成代码内部的 unsafe 块中，调用方无需编写 unsafe：
+///
+/// ```
+/// use rust_advanced::selfref;
+///
+/// selfref!(MyBuf, Vec<u8>);
+///
+/// let mut buf = MyBuf::new(vec![1, 2]);
+/// buf.as_mut().update(vec![3]);
+/// assert_eq!(buf.get_ref(), &[3]);
+/// ```
+///
+/// 插入的 `PhantomPinned` 让类型不是 `Unpin`，无法从 `Pin` 中取回移动：
+///
+/// ```compile_fail
+/// use rust_advanced::selfref;
+///
+/// selfref!(MyBuf, Vec<u8>);
+///
+/// let moved: Box<MyBuf> = core::pin::Pin::into_inner(MyBuf::new(vec![1]));
+/// ```
+///
+/// 更多展开后的行为见 `tests/自引用宏.rs`
+#[macro_export]
+macro_rules! selfref {
+    ($(#[$meta:meta])* $vis:vis $name:ident, $payload:ty) => {
+        $(#[$meta])*
+        $vis struct $name {
+            data: $payload,
+            ptr: ::core::option::Option<::core::ptr::NonNull<$payload>>,
+            _pin: ::core::marker::PhantomPinned,
+        }
+
+        impl $name {
+            /// 创建固定在堆上的实例，固定之后才建立自引用
+            #[allow(dead_code)]
+            $vis fn new(data: $payload) -> ::core::pin::Pin<$crate::__Box<Self>> {
+                let mut pinned = $crate::__Box::pin($name {
+                    data,
+                    ptr: ::core::option::Option::None,
+                    _pin: ::core::marker::PhantomPinned,
+                });
+                pinned.as_mut().sync_ptr();
+                pinned
+            }
+
+            /// 通过自引用读取数据
+            #[allow(dead_code)]
+            $vis fn get_ref(&self) -> &$payload {
+                let ptr = self.ptr.expect("自引用在 new 中已建立");
+                // SAFETY：ptr 指向自身的 data 字段，实例固定在堆上，地址不会变化
+                unsafe { ptr.as_ref() }
+            }
+
+            /// 替换数据并同步自引用
+            #[allow(dead_code)]
+            $vis fn update(mut self: ::core::pin::Pin<&mut Self>, data: $payload) {
+                // 仅替换字段内容，不移动实例
+                unsafe { self.as_mut().get_unchecked_mut() }.data = data;
+                self.sync_ptr();
+            }
+
+            // 从固定位置的 data 字段重新推导自引用
+            fn sync_ptr(self: ::core::pin::Pin<&mut Self>) {
+                // 仅修改字段，不移动实例
+                let this = unsafe { self.get_unchecked_mut() };
+                this.ptr = ::core::option::Option::Some(::core::ptr::NonNull::from(&this.data));
+            }
+        }
+    };
+}
+
+/// 在栈上创建并固定有自引用的实例：`optional_self_ref_local!(x = value)`
+///
+/// 展开为 `core::pin::pin!` 栈固定 + `init_self_ref`，`x` 的类型是
+/// `Pin<&mut InlineSelfRef<T, WithRef>>`。数据内联在栈上的结构体中，容器与数据都不做堆分配
+/// （`OptionalSelfRef` 总是把数据放在 `Box<T>` 中，因此这里使用 [`InlineSelfRef`](crate::pin::InlineSelfRef)）：
+///
+/// ```
+/// use rust_advanced::optional_self_ref_local;
+///
+/// optional_self_ref_local!(x = [1u8, 2, 3]);
+/// assert_eq!(x.get_ref(), Some(&[1, 2, 3]));
+/// assert!(core::ptr::eq(x.get_ref().unwrap(), x.data()));
+/// ```
+///
+/// 绑定只在当前作用域内有效，离开作用域后不能再使用：
+///
+/// ```compile_fail
+/// use rust_advanced::optional_self_ref_local;
+///
+/// let escaped;
+/// {
+///     optional_self_ref_local!(x = 1);
+///     escaped = x.get_ref();
+/// }
+/// println!("{:?}", escaped);
+/// ```
+#[macro_export]
+macro_rules! optional_self_ref_local {
+    ($name:ident = $value:expr) => {
+        let mut $name = ::core::pin::pin!(
+            $crate::pin::InlineSelfRef::new_no_ref($value).into_with_ref()
+        );
+        $name.as_mut().init_self_ref();
+    };
+}
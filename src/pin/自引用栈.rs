@@ -0,0 +1,75 @@
+use core::pin::Pin;
+use alloc::{boxed::Box, vec::Vec};
+
+use super::SelfRef;
+
+/// 自引用实例的栈：只能从栈顶压入、弹出（LIFO）
+///
+/// 每个元素都是独立分配、独立固定的 `Pin<Box<SelfRef>>`，自引用的有效性只取决于元素自身；
+/// Vec 扩容只搬动 Box 指针，不会移动堆上的 SelfRef。栈只交出共享引用，
+/// 弹出时把固定的 Box 原样交还，元素在栈中和弹出后都不会离开原来的地址
+#[derive(Debug, Default)]
+pub struct SelfRefStack {
+    items: Vec<Pin<Box<SelfRef>>>,
+}
+
+impl SelfRefStack {
+    /// 创建空栈
+    pub fn new() -> Self {
+        SelfRefStack { items: Vec::new() }
+    }
+
+    /// 把固定的实例压入栈顶，所有权交给栈
+    pub fn push(&mut self, item: Pin<Box<SelfRef>>) {
+        self.items.push(item);
+    }
+
+    /// 弹出栈顶实例，固定的 Box 原样交还，自引用仍然有效
+    pub fn pop(&mut self) -> Option<Pin<Box<SelfRef>>> {
+        self.items.pop()
+    }
+
+    /// 查看栈顶实例
+    pub fn peek(&self) -> Option<&SelfRef> {
+        self.items.last().map(|item| &**item)
+    }
+
+    /// 栈中的实例个数
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// 栈是否为空
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SelfRefStack;
+    use crate::pin::SelfRef;
+
+    #[test]
+    fn popped_items_keep_their_address() {
+        let mut stack = SelfRefStack::new();
+        assert!(stack.is_empty());
+        let first = SelfRef::new("栈底");
+        let first_addr = first.get_ref().as_ptr();
+        stack.push(first);
+        // 扩容只搬动 Box 指针，元素本身不动
+        for i in 0..32 {
+            stack.push(SelfRef::new(if i % 2 == 0 { "偶" } else { "奇" }));
+        }
+        assert_eq!(stack.len(), 33);
+        assert_eq!(stack.peek().map(SelfRef::get_ref), Some("奇"));
+        while stack.len() > 1 {
+            assert!(stack.pop().unwrap().validate());
+        }
+        let first = stack.pop().unwrap();
+        assert_eq!(first.get_ref(), "栈底");
+        assert_eq!(first.get_ref().as_ptr(), first_addr);
+        assert!(first.validate());
+        assert!(stack.pop().is_none());
+    }
+}
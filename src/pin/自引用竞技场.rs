@@ -0,0 +1,123 @@
+use core::ops::Range;
+use alloc::{string::String, vec, vec::Vec};
+
+// 默认的块容量（字节）
+const DEFAULT_CHUNK_CAPACITY: usize = 4096;
+
+/// 自引用字符串竞技场：许多短字符串共享预先分配好的缓冲区，以句柄访问
+///
+/// 缓冲区按块分配：每块创建时就预留好全部容量，之后只在容量之内追加，绝不重新分配，
+/// 已交出的视图因此始终有效。容量足够时（见 [`with_capacity`](Self::with_capacity)）
+/// 所有字符串都位于同一次分配中；放不下时另开新块，旧块原样保留
+#[derive(Debug)]
+pub struct SelfRefArena {
+    // 移动 Vec 或 Vec 扩容只会移动各个 String 的头部，不会移动它们的堆缓冲区
+    chunks: Vec<String>,
+    // 每个句柄对应的视图：指向所在块缓冲区的裸指针，以及它在该块内的字节区间
+    views: Vec<(*const str, Range<usize>)>,
+    chunk_capacity: usize,
+}
+
+impl SelfRefArena {
+    /// 创建空的竞技场，按默认块容量分配
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CHUNK_CAPACITY)
+    }
+
+    /// 创建竞技场，并预留 `capacity` 字节作为首块；总长度不超过它时只有一次缓冲区分配
+    pub fn with_capacity(capacity: usize) -> Self {
+        SelfRefArena {
+            chunks: vec![String::with_capacity(capacity)],
+            views: Vec::new(),
+            chunk_capacity: capacity,
+        }
+    }
+
+    /// 存入字符串，返回用于读取的句柄
+    pub fn push(&mut self, s: &str) -> usize {
+        let fits = self
+            .chunks
+            .last()
+            .is_some_and(|chunk| chunk.capacity() - chunk.len() >= s.len());
+        if !fits {
+            // 当前块放不下：另开新块，超长的字符串独占一块
+            self.chunks
+                .push(String::with_capacity(self.chunk_capacity.max(s.len())));
+        }
+
+        let chunk = self.chunks.last_mut().expect("至少有一个块");
+        let start = chunk.len();
+        // 容量已检查，push_str 不会重新分配缓冲区
+        chunk.push_str(s);
+        let range = start..chunk.len();
+        self.views.push((&chunk[range.clone()] as *const str, range));
+        self.views.len() - 1
+    }
+
+    /// 通过句柄读取字符串，句柄无效时返回 `None`
+    pub fn get(&self, handle: usize) -> Option<&str> {
+        let (ptr, range) = self.views.get(handle)?;
+        // SAFETY：块缓冲区只追加、从不重新分配或截断，ptr 指向的字节在竞技场存续期间不变
+        let view = unsafe { &**ptr };
+        debug_assert_eq!(view.len(), range.len());
+        Some(view)
+    }
+
+    /// 已存入的字符串个数
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    /// 是否尚未存入任何字符串
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+
+    /// 已分配的块数
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
+impl Default for SelfRefArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, vec::Vec};
+
+    use super::SelfRefArena;
+
+    #[test]
+    fn views_stay_put_across_pushes() {
+        let mut arena = SelfRefArena::with_capacity(16);
+        let handles: Vec<_> = (0..50).map(|i| arena.push(&format!("词{}", i))).collect();
+        let addrs: Vec<_> = handles.iter().map(|&h| arena.get(h).unwrap().as_ptr()).collect();
+        for i in 50..200 {
+            arena.push(&format!("词{}", i));
+        }
+        for (i, (&handle, &addr)) in handles.iter().zip(&addrs).enumerate() {
+            assert_eq!(arena.get(handle), Some(format!("词{}", i).as_str()));
+            assert_eq!(arena.get(handle).unwrap().as_ptr(), addr);
+        }
+        assert!(arena.chunk_count() > 1);
+    }
+
+    #[test]
+    fn single_chunk_when_capacity_suffices() {
+        let mut arena = SelfRefArena::with_capacity(6);
+        let a = arena.push("abc");
+        let b = arena.push("def");
+        assert_eq!(arena.chunk_count(), 1);
+        assert_eq!(arena.get(b).unwrap().as_ptr(), arena.get(a).unwrap().as_ptr().wrapping_add(3));
+        // 超长的字符串独占新块
+        let long = arena.push("超过容量");
+        assert_eq!((arena.get(long), arena.chunk_count()), (Some("超过容量"), 2));
+        assert_eq!(arena.get(long + 1), None);
+        assert!(!arena.is_empty());
+        assert!(SelfRefArena::new().is_empty());
+    }
+}
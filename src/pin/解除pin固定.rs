@@ -0,0 +1,1171 @@
+use core::pin::Pin;
+use core::marker::{PhantomData, PhantomPinned};
+use core::ptr::NonNull;
+use core::fmt;
+use core::future::Future;
+use core::mem::ManuallyDrop;
+#[cfg(feature = "allocator_api")]
+use core::alloc::Allocator;
+use core::task::{Context, Poll};
+#[cfg(debug_assertions)]
+use core::sync::atomic::{AtomicUsize, Ordering};
+use alloc::{boxed::Box, vec::Vec};
+use super::{InvariantViolation, RebindError};
+
+/// 类型状态标记：无自引用（self_ref 恒为 None → Unpin，可自由移动）
+#[derive(Debug)]
+pub struct NoRef;
+
+/// 类型状态标记：有（或将要建立）自引用（!Unpin → 必须固定后才能建立自引用）
+///
+/// 是否 Unpin 在编译期由状态决定，WithRef 实例一旦固定就无法再取出或移动：
+///
+/// ```compile_fail
+/// use core::pin::Pin;
+/// use rust_advanced::pin::OptionalSelfRef;
+///
+/// let pinned = OptionalSelfRef::new_with_ref(1);
+/// let _unpinned = Pin::into_inner(pinned); // 需要 Unpin
+/// ```
+///
+/// ```compile_fail
+/// use rust_advanced::pin::OptionalSelfRef;
+///
+/// let mut a = OptionalSelfRef::new_with_ref(1);
+/// let mut b = OptionalSelfRef::new_with_ref(2);
+/// core::mem::swap(&mut *a, &mut *b); // DerefMut 需要 Unpin
+/// ```
+///
+/// ```compile_fail
+/// use core::pin::Pin;
+/// use rust_advanced::pin::OptionalSelfRef;
+///
+/// let mut deferred = OptionalSelfRef::new_no_ref(1).into_with_ref();
+/// let _pinned = Pin::new(&mut deferred); // Pin::new 需要 Unpin
+/// ```
+///
+/// NoRef 状态则全部允许：
+///
+/// ```
+/// use core::pin::Pin;
+/// use rust_advanced::pin::OptionalSelfRef;
+///
+/// let mut a = Box::pin(OptionalSelfRef::new_no_ref(1));
+/// let mut b = Box::pin(OptionalSelfRef::new_no_ref(2));
+/// core::mem::swap(&mut *a, &mut *b);
+/// let _unpinned = Pin::into_inner(a);
+/// ```
+#[derive(Debug)]
+pub struct WithRef;
+
+/// 核心类型：可选自引用的容器（移除易冲突的泛型生命周期 'a）
+///
+/// 数据放在 `Box<T>` 中，因此 `T` 可以是 `str`、`[u8]` 等不定长类型（见 `from_boxed`），
+/// 此时 self_ref 是胖指针，长度信息随指针一起保存
+///
+/// WithRef 状态下 data 是结构化固定的：固定后只会交出 `Pin<&mut T>`，
+/// 需要 `&mut T` 或移出数据的操作都要求 `T: Unpin`
+pub struct OptionalSelfRef<T: ?Sized, S = NoRef> {
+    // 堆分配数据（地址固定，生命周期稳定）
+    data: Box<T>,
+    // 可选自引用：用非空裸指针替代 &T，避开生命周期陷阱（Pin 保证安全）
+    // NonNull 的空值优化使 Option<NonNull<T>> 与 *const T 大小相同
+    self_ref: Option<NonNull<T>>,
+    // 数据的修改代数：每次交出可变访问都加一，用于识别过期的 RefToken
+    generation: u64,
+    // 可选的显示名称，由 OptionalSelfRefBuilder 设置，Display 时作为前缀
+    name: Option<Box<str>>,
+    // 类型状态：NoRef / WithRef，在编译期决定是否 Unpin
+    _state: PhantomData<S>,
+    // 标记：默认 !Unpin，仅 NoRef 状态通过 impl Unpin 覆盖
+    _pin: PhantomPinned,
+}
+
+/// 始终可移动的无自引用容器：即 NoRef 状态的 OptionalSelfRef，
+/// 类型层面保证不持有自引用，因此 Unpin 是真实成立的，而非「兜底」声明
+pub type PlainBox<T> = OptionalSelfRef<T, NoRef>;
+
+/// 自引用凭证：打包自引用指针与当时的修改代数，见 [`OptionalSelfRef::get_ref_token`]
+///
+/// 凭证本身不借用容器，可以跨越修改长期保存；只有代数仍然一致时才能通过
+/// [`redeem`](OptionalSelfRef::redeem) 换回引用，数据被修改过则兑换失败
+#[derive(Debug)]
+pub struct RefToken<T: ?Sized> {
+    ptr: NonNull<T>,
+    generation: u64,
+}
+
+// 凭证只是指针加代数，可以随意复制，不要求 T: Clone
+impl<T: ?Sized> Clone for RefToken<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for RefToken<T> {}
+
+// Debug 报告自引用的不变量：数据、数据地址、自引用目标地址，
+// 以及 invariant_ok（没有自引用，或自引用恰好指向自身数据）
+impl<T: fmt::Debug + ?Sized, S> fmt::Debug for OptionalSelfRef<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OptionalSelfRef")
+            .field("data", &&*self.data)
+            .field("data_addr", &format_args!("{:p}", &*self.data as *const T))
+            .field("self_ref", &self.self_ref.map(|ptr| ptr.as_ptr() as *const T))
+            .field("invariant_ok", &self.is_ref_valid())
+            .field("generation", &self.generation)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+// 实现 Display 方便打印；`{:#}` 额外输出多行的地址报告
+impl<T: fmt::Display + ?Sized, S> fmt::Display for OptionalSelfRef<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "[{}] ", name)?;
+        }
+        write!(f, "数据：{}，自引用状态：{}",
+            self.data,
+            if self.self_ref.is_some() { "有自引用" } else { "无自引用" }
+        )?;
+        if f.alternate() {
+            let data = &*self.data as *const T;
+            writeln!(f)?;
+            writeln!(f, "  容器地址: {:p}", self as *const Self)?;
+            writeln!(f, "  数据地址: {:p}", data)?;
+            match self.self_ref {
+                Some(ptr) => writeln!(f, "  自引用目标: {:p}", ptr)?,
+                None => writeln!(f, "  自引用目标: 无")?,
+            }
+            write!(f, "  地址一致: {}", self.is_ref_valid())?;
+        }
+        Ok(())
+    }
+}
+
+// 条件性 Unpin：仅 NoRef 状态实现 Unpin
+// 注：Rust 无法基于运行时字段值实现 Unpin，因此把「有无自引用」提升为类型状态：
+// NoRef 实例的 self_ref 恒为 None，移动安全；WithRef 实例保持 PhantomPinned 带来的 !Unpin
+impl<T: ?Sized> Unpin for OptionalSelfRef<T, NoRef> {}
+
+// SAFETY：self_ref 只会指向自身拥有的 Box<T>，这块堆内存随容器一起转移，
+// 不存在与其他实例共享的状态。因此 T: Send 时整个容器可以跨线程移动；
+// 共享引用只会交出 &T，T: Sync 时容器即可跨线程共享
+unsafe impl<T: Send + ?Sized, S> Send for OptionalSelfRef<T, S> {}
+unsafe impl<T: Sync + ?Sized, S> Sync for OptionalSelfRef<T, S> {}
+
+impl<T> OptionalSelfRef<T, NoRef> {
+    /// 1. 创建「无自引用」的实例（可 Unpin → 自由移动、解除固定）
+    pub fn new_no_ref(data: T) -> Self {
+        Self::from_boxed(Box::new(data))
+    }
+
+    /// 在指定的分配器中创建无自引用的实例（需要 nightly 与 `allocator_api` feature），
+    /// 分配器的适用范围同 [`new_with_ref_in`](OptionalSelfRef::new_with_ref_in)
+    #[cfg(feature = "allocator_api")]
+    pub fn new_no_ref_in<A: Allocator>(data: T, alloc: A) -> Box<Self, A> {
+        Box::new_in(Self::new_no_ref(data), alloc)
+    }
+
+    /// 变换数据，得到新的无自引用实例
+    ///
+    /// 两种状态都有 `map_data`；以路径语法调用时需写明状态，例如 `OptionalSelfRef::<_, NoRef>::map_data(x, f)`
+    pub fn map_data<U, F: FnOnce(T) -> U>(self, f: F) -> OptionalSelfRef<U, NoRef> {
+        OptionalSelfRef::new_no_ref(f(*self.into_box()))
+    }
+}
+
+impl<T: ?Sized> OptionalSelfRef<T, NoRef> {
+    /// 直接接管已有的 Box（可以是 `Box<str>`、`Box<[u8]>` 等不定长数据），不再额外分配
+    pub fn from_boxed(data: Box<T>) -> Self {
+        OptionalSelfRef {
+            data,
+            self_ref: None,
+            generation: 0,
+            name: None,
+            _state: PhantomData,
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// 切换到 WithRef 状态：此时仍未建立自引用，固定之前可以继续移动，
+    /// 固定后再调用 [`init_self_ref`](OptionalSelfRef::init_self_ref) 安装自引用
+    pub fn into_with_ref(mut self) -> OptionalSelfRef<T, WithRef> {
+        let generation = self.generation;
+        let name = self.name.take();
+        OptionalSelfRef {
+            data: self.into_box(),
+            self_ref: None,
+            generation,
+            name,
+            _state: PhantomData,
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// 固定并建立自引用：把可移动的实例一步转换为固定的、有自引用的实例
+    ///
+    /// 等价于 `Box::pin(self.into_with_ref())` 后调用 `init_self_ref`，数据所在的堆分配不变
+    pub fn upgrade(self) -> Pin<Box<OptionalSelfRef<T, WithRef>>> {
+        let mut pinned = Box::pin(self.into_with_ref());
+        pinned.as_mut().init_self_ref();
+        pinned
+    }
+
+    /// 可变访问数据：无自引用、未被固定，直接交出 `&mut T`
+    pub fn get_data_mut(&mut self) -> &mut T {
+        self.generation = self.generation.wrapping_add(1);
+        &mut self.data
+    }
+
+    /// 交出内部的 Box，数据不移动、不重新分配
+    ///
+    /// 孤儿规则不允许为外部的 `Box<T>` / `T` 实现 `From<OptionalSelfRef<T>>`，
+    /// 反方向的转换因此以方法提供；取出 `T` 见 [`take_data`](OptionalSelfRef::take_data)
+    pub fn into_boxed(self) -> Box<T> {
+        self.into_box()
+    }
+}
+
+impl<T> OptionalSelfRef<T, WithRef> {
+    /// 2. 创建「有自引用」的实例（!Unpin → 必须 Pin<Box<T>> 固定）
+    pub fn new_with_ref(data: T) -> Pin<Box<Self>> {
+        // 步骤1：将实例封装为 Pin<Box<Self>>（堆固定，地址不变）
+        let mut pinned = Self::new_pinned(data);
+
+        // 步骤2：在固定后的实例上建立自引用
+        pinned.as_mut().init_self_ref();
+
+        // 返回固定后的实例（无生命周期冲突）
+        pinned
+    }
+
+    /// 创建已固定、但尚未建立自引用的实例，之后可随时调用 [`init_self_ref`](Self::init_self_ref)
+    pub fn new_pinned(data: T) -> Pin<Box<Self>> {
+        Box::pin(OptionalSelfRef::new_no_ref(data).into_with_ref())
+    }
+
+    /// 在指定的分配器中创建有自引用的实例（需要 nightly 与 `allocator_api` feature）
+    ///
+    /// 限制：分配器只用于容器本身；data 字段是 `Box<T>`，仍由全局分配器分配——
+    /// 让 data 也使用 `A` 需要给类型增加分配器参数，而 stable 上无法写出 `Box<T, A>`。
+    /// 需要全部分配都来自 `A` 时改用 [`InlineSelfRef::new_with_ref_in`](super::InlineSelfRef::new_with_ref_in)：
+    /// 数据内联在容器中，只有一次分配。
+    /// 已经分配好数据的调用方可以用 [`from_boxed_with_ref`](Self::from_boxed_with_ref) 直接接管。
+    /// 与 `Box::pin_in` 相同要求 `A: 'static`：分配器若先于实例失效，固定的内存就可能未经析构被回收
+    #[cfg(feature = "allocator_api")]
+    pub fn new_with_ref_in<A: Allocator + 'static>(data: T, alloc: A) -> Pin<Box<Self, A>> {
+        let mut pinned = Box::pin_in(OptionalSelfRef::new_no_ref(data).into_with_ref(), alloc);
+        pinned.as_mut().init_self_ref();
+        pinned
+    }
+
+    /// 变换固定实例的数据：变换结果放入新的固定实例，
+    /// 原实例有自引用时，新实例的自引用指向它自己的新数据，绝不指向旧数据
+    ///
+    /// 旧容器被整体消费，不会留下指向旧数据的自引用。需要把数据移出固定的容器，因此要求 `T: Unpin`
+    pub fn map_data<U, F: FnOnce(T) -> U>(
+        self: Pin<Box<Self>>,
+        f: F,
+    ) -> Pin<Box<OptionalSelfRef<U, WithRef>>>
+    where
+        T: Unpin,
+    {
+        let had_ref = self.self_ref.is_some();
+        let plain = Self::clear_ref(self);
+        let mut mapped = OptionalSelfRef::new_pinned(f(*plain.into_box()));
+        if had_ref {
+            mapped.as_mut().init_self_ref();
+        }
+        mapped
+    }
+
+    /// 替换固定实例的数据并返回旧值：新值原地写入已有的 Box，分配地址不变，self_ref 无需修正
+    ///
+    /// 旧值被移出固定的位置，因此要求 `T: Unpin`
+    pub fn replace_data(mut self: Pin<&mut Self>, new: T) -> T
+    where
+        T: Unpin,
+    {
+        let old = core::mem::replace(self.as_mut().get_mut_data(), new);
+        debug_verify!(self);
+        old
+    }
+
+    /// 交换固定实例的数据并返回旧值；有自引用时交换后从 Box 重新推导，指向新数据
+    ///
+    /// 旧值移出后交给调用方，自引用绝不会指向它；新值写入的仍是原来的 Box，
+    /// 与 [`replace_data`](Self::replace_data) 不同的是这里经由 [`with_mut`](Self::with_mut)
+    /// 显式地重新同步自引用。旧值被移出固定的位置，因此要求 `T: Unpin`
+    pub fn swap_data(self: Pin<&mut Self>, new: T) -> T
+    where
+        T: Unpin,
+    {
+        self.with_mut(|data| core::mem::replace(data, new))
+    }
+
+    /// 消费固定的实例并取出数据：自引用随容器一起销毁，之后才移出 `T`
+    ///
+    /// 与 [`clear_ref`](Self::clear_ref) 相同，移出结构化固定的数据要求 `T: Unpin`
+    pub fn into_data(this: Pin<Box<Self>>) -> T
+    where
+        T: Unpin,
+    {
+        *Self::clear_ref(this).into_box()
+    }
+
+    /// 安全地取出数据：仅在没有自引用时成功；仍有自引用时原样交还固定的 Box
+    ///
+    /// 与 [`into_unpinned`](Self::into_unpinned) 的判断相同，区别是直接交出 `T`；
+    /// 数据是结构化固定的，移出仍要求 `T: Unpin`
+    pub fn try_into_unpinned(this: Pin<Box<Self>>) -> Result<T, Pin<Box<Self>>>
+    where
+        T: Unpin,
+    {
+        Self::into_unpinned(this).map(|unpinned| *unpinned.into_box())
+    }
+}
+
+impl<T: ?Sized> OptionalSelfRef<T, WithRef> {
+    /// 接管已有的 Box 并建立自引用（支持 `Box<str>`、`Box<[u8]>` 等不定长数据）
+    ///
+    /// 不复制数据，数据地址与调用方看到的 Box 地址相同：Box 先移入结构体并固定，
+    /// 自引用随后从结构体内的 Box 取得，而不是从调用方原来的 Box 变量取得
+    pub fn from_boxed_with_ref(data: Box<T>) -> Pin<Box<Self>> {
+        let mut pinned = Box::pin(OptionalSelfRef::from_boxed(data).into_with_ref());
+        pinned.as_mut().init_self_ref();
+        pinned
+    }
+
+    /// 在已固定的实例上建立自引用（可配合 `Box::pin` 或 `pin!` 使用）
+    ///
+    /// 幂等：返回 `true` 表示本次新建了自引用；已存在自引用时不做任何修改，返回 `false`。
+    /// 接收 `Pin<&mut Self>`，因此只能作用于已固定的实例，保证自引用建立后地址不再变化
+    pub fn init_self_ref(mut self: Pin<&mut Self>) -> bool {
+        let fields = self.as_mut().as_pin_mut();
+        if fields.self_ref.is_some() {
+            return false;
+        }
+        // 裸指针指向堆上的 data（地址固定，永久有效）
+        store_self_ref(fields.self_ref, Some(fields.data));
+        debug_verify!(self);
+        true
+    }
+
+    /// 结构化固定的可变访问：交出 `Pin<&mut T>`
+    ///
+    /// 数据存放在 Box 中，修改数据（包括 String 的追加、重新分配缓冲区）都不会移动 Box 本身，
+    /// 因此 self_ref 指向的地址始终有效；返回 Pin 则保证 `T` 自身同样不会被移动。
+    /// 交出可变访问即视为修改，修改代数加一
+    pub fn get_data_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        let fields = self.as_pin_mut();
+        *fields.generation = fields.generation.wrapping_add(1);
+        let data = fields.data;
+        // SAFETY：data 来自固定实例拥有的 Box，生命周期与 self 绑定；
+        // 固定期间绝不移出 T（移出的操作都要求 T: Unpin），因此可以交出 Pin<&mut T>
+        unsafe { Pin::new_unchecked(&mut *data.as_ptr()) }
+    }
+
+    /// 可变访问数据：`T: Unpin` 时直接交出 `&mut T`
+    ///
+    /// 调用方拿到的是 `&mut T` 而非 `&mut Box<T>`，无法替换 Box 本身，
+    /// self_ref 指向的 Box 内地址始终不变。注意 self_ref 指向的是 `T` 这个值本身，
+    /// 而不是 `T` 内部的缓冲区：例如 String 追加后缓冲区可能重新分配，但 `get_ref`
+    /// 返回的 `&String` 仍然有效并反映最新内容；即使 `mem::replace` 整个值也只是原地写入
+    pub fn get_mut_data(self: Pin<&mut Self>) -> &mut T
+    where
+        T: Unpin,
+    {
+        Pin::into_inner(self.get_data_mut())
+    }
+
+    /// 在闭包内以 `&mut T` 修改数据，结束后若有自引用则重新同步
+    ///
+    /// 闭包只拿到 `&mut T`：
+    /// - 拿不到 `&mut Box<T>`，无法 `mem::replace` / `mem::swap` 整个 Box，也就无法让 self_ref 悬垂；
+    /// - 拿不到 self_ref 本身，无法改写自引用；
+    /// - 可以原地 `mem::replace` 整个 `T`（要求 `T: Unpin`，移出旧值不违反固定约定），
+    ///   也可以让 String、Vec 等重新分配内部缓冲区，self_ref 指向的是 `T` 本身，不受影响；
+    /// - 闭包内产生的借用无法逃逸到闭包之外，由借用检查保证。
+    pub fn with_mut<R>(mut self: Pin<&mut Self>, f: impl FnOnce(&mut T) -> R) -> R
+    where
+        T: Unpin,
+    {
+        let result = f(self.as_mut().get_mut_data());
+        let fields = self.as_mut().as_pin_mut();
+        if fields.self_ref.is_some() {
+            store_self_ref(fields.self_ref, Some(fields.data));
+        }
+        debug_verify!(self);
+        result
+    }
+
+    /// 清除自引用，之后实例不再依赖自身地址
+    pub fn clear_self_ref(mut self: Pin<&mut Self>) {
+        store_self_ref(self.as_mut().as_pin_mut().self_ref, None);
+        debug_verify!(self);
+    }
+
+    /// 把自引用重新指向 `new_target`：目标必须位于自身数据的分配之内，否则返回 `Err`
+    ///
+    /// 用于校验经由裸指针（例如 FFI）传回的地址，拒绝栈上的局部变量、其他实例的数据等；
+    /// 自引用的类型是 `&T`，位于数据内部的只能是数据本身（不定长数据的子切片同样被拒绝）。
+    /// 校验通过后并不保存 `new_target` 的指针，而是从 Box 重新推导，来源与其他自引用一致
+    pub fn rebind_ref(mut self: Pin<&mut Self>, new_target: &T) -> Result<(), RebindError> {
+        let data = self.data.as_ref();
+        let (start, len) = (data as *const T as *const u8 as usize, core::mem::size_of_val(data));
+        let target = new_target as *const T as *const u8 as usize;
+        let target_len = core::mem::size_of_val(new_target);
+        if target < start || target - start > len || target_len > len - (target - start) {
+            return Err(RebindError::OutsideData { target, data: start });
+        }
+        if target != start || target_len != len {
+            return Err(RebindError::NotWholeData { offset: target - start, len: target_len });
+        }
+        let fields = self.as_mut().as_pin_mut();
+        store_self_ref(fields.self_ref, Some(fields.data));
+        debug_verify!(self);
+        Ok(())
+    }
+
+    /// 交换两个固定实例的数据，容器本身都不移动；有自引用的一方随后指向自己的新数据
+    ///
+    /// 交换的是两个 Box 的所有权，`T` 留在原来的堆分配中、没有被移动，
+    /// 因此不要求 `T: Unpin`，也不会重新分配。双方的修改代数都加一，
+    /// 交换前取得的 [`RefToken`] 随之失效
+    pub fn swap(mut a: Pin<&mut Self>, mut b: Pin<&mut Self>) {
+        // SAFETY：只改写两个容器的 data 字段，容器不移动；
+        // 结构化固定的 T 仍在原来的堆地址上，直到随新的所有者一起析构
+        unsafe {
+            let a_data = &mut a.as_mut().get_unchecked_mut().data;
+            let b_data = &mut b.as_mut().get_unchecked_mut().data;
+            core::mem::swap(a_data, b_data);
+        }
+        for pinned in [a.as_mut(), b.as_mut()] {
+            let fields = pinned.as_pin_mut();
+            *fields.generation = fields.generation.wrapping_add(1);
+            // 旧的 self_ref 指向对方的数据，从自己的 Box 重新推导
+            if fields.self_ref.is_some() {
+                store_self_ref(fields.self_ref, Some(fields.data));
+            }
+        }
+        debug_verify!(a);
+        debug_verify!(b);
+    }
+
+    /// 泄漏固定的实例，经由自引用交出 `&'static T`，适合全局单例
+    ///
+    /// 尚未建立自引用时先建立。泄漏的 Box 永远不会被移动或释放，自引用因此永久有效；
+    /// debug 构建下 [`live_self_ref_count`] 也会永久多计一个
+    pub fn leak(this: Pin<Box<Self>>) -> &'static T
+    where
+        T: 'static,
+    {
+        let mut this = this;
+        this.as_mut().init_self_ref();
+        // SAFETY：泄漏后实例既不会被移动也不会被释放，固定的约定在之后永远成立
+        let leaked: &'static Self = Box::leak(unsafe { Pin::into_inner_unchecked(this) });
+        leaked.get_ref().expect("自引用已建立")
+    }
+
+    /// 安全解除固定：仅在没有自引用时成功；仍有自引用时原样交还固定的 Box
+    ///
+    /// WithRef 状态下数据是结构化固定的，可能已经以 `Pin<&mut T>` 交出（`get_data_mut`、`Future`），
+    /// 解除固定后数据可以被移出，因此要求 `T: Unpin`：
+    ///
+    /// ```compile_fail
+    /// use core::marker::PhantomPinned;
+    /// use rust_advanced::pin::OptionalSelfRef;
+    ///
+    /// let _ = OptionalSelfRef::into_unpinned(OptionalSelfRef::new_pinned(PhantomPinned));
+    /// ```
+    pub fn into_unpinned(this: Pin<Box<Self>>) -> Result<Box<Self>, Pin<Box<Self>>>
+    where
+        T: Unpin,
+    {
+        if this.self_ref.is_some() {
+            return Err(this);
+        }
+        // 没有自引用时容器不依赖自身地址，T: Unpin 时数据也不依赖，移出 Pin 不会产生悬垂指针
+        Ok(unsafe { Pin::into_inner_unchecked(this) })
+    }
+
+    /// 清除自引用并交出可自由移动的 [`PlainBox`]
+    ///
+    /// 解除固定的规则：
+    /// - 只有 `self_ref` 为 `None` 时，实例才不依赖自身地址，才允许离开 Pin；
+    /// - `clear_ref` 先清除自引用、再拆开容器，data 所在的堆分配原样转交，数据本身不会被移动；
+    /// - 之后如需再次建立自引用，调用 `into_with_ref` 重新固定并 `init_self_ref` 即可。
+    ///
+    /// WithRef 状态下数据是结构化固定的（见 [`get_data_mut`](Self::get_data_mut)），
+    /// 而 PlainBox 允许移出数据，因此要求 `T: Unpin`
+    pub fn clear_ref(mut this: Pin<Box<Self>>) -> PlainBox<T>
+    where
+        T: Unpin,
+    {
+        this.as_mut().clear_self_ref();
+        let mut this = match Self::into_unpinned(this) {
+            Ok(this) => this,
+            Err(_) => unreachable!("自引用已清除"),
+        };
+        let generation = this.generation;
+        let name = this.name.take();
+        OptionalSelfRef {
+            data: (*this).into_box(),
+            self_ref: None,
+            generation,
+            name,
+            _state: PhantomData,
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+impl<T: ?Sized, S> OptionalSelfRef<T, S> {
+    /// 3. 安全获取自引用的值（封装 unsafe，保证安全）
+    pub fn get_ref(&self) -> Option<&T> {
+        debug_assert!(self.is_ref_valid(), "自引用与数据地址不一致");
+        self.self_ref.map(|ptr| {
+            // SAFETY：self_ref 只会在实例固定后建立，且只指向自身拥有的 Box<T>；
+            // Pin 保证实例在自引用存续期间不被移动或释放，Box<T> 也随之保持有效
+            unsafe { ptr.as_ref() }
+        })
+    }
+
+    /// 以固定视图读取自引用：`T` 本身对地址敏感（自引用结构体、Future）时保留固定信息
+    pub fn get_ref_pinned(self: Pin<&Self>) -> Option<Pin<&T>> {
+        // SAFETY：实例固定期间 Box<T> 所在的分配既不会被移动也不会被释放，
+        // 且 T 只会以 Pin<&mut T> 的形式交出（见 get_data_mut），满足结构化固定
+        // Pin 自带同名的 get_ref，这里先取出 &Self 再调用本类型的 get_ref
+        Pin::get_ref(self).get_ref().map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+
+    /// 在闭包内使用自引用，借用的范围一目了然；没有自引用时返回 `None`
+    pub fn with_ref<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.get_ref().map(f)
+    }
+
+    /// 有自引用时以它调用一次闭包，没有时什么也不做；解引用仍集中在 [`get_ref`](Self::get_ref)
+    pub fn for_each_ref<F: FnMut(&T)>(&self, mut f: F) {
+        if let Some(data) = self.get_ref() {
+            f(data);
+        }
+    }
+
+    /// 同时访问数据本身与自引用视图
+    pub fn with_data_and_ref<R>(&self, f: impl FnOnce(&T, Option<&T>) -> R) -> R {
+        f(&self.data, self.get_ref())
+    }
+
+    /// 检查自引用是否仍指向自身的数据：没有自引用时视为有效
+    ///
+    /// 只比较地址（不定长数据还会比较长度），不解引用 self_ref，可放心用于 `debug_assert!`
+    pub fn is_ref_valid(&self) -> bool {
+        self.verify_invariant().is_ok()
+    }
+
+    /// 校验不变量：有自引用时必须恰好指向自身数据，失败时给出期望与实际地址
+    pub fn verify_invariant(&self) -> Result<(), InvariantViolation> {
+        let expected = &*self.data as *const T as *const u8 as usize;
+        match self.self_ref {
+            Some(ptr) if !core::ptr::eq(ptr.as_ptr(), &*self.data) => {
+                Err(InvariantViolation::SelfRefMismatch {
+                    expected,
+                    actual: ptr.as_ptr() as *const u8 as usize,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 自引用相对数据起始处的字节偏移，没有自引用时返回 `None`
+    ///
+    /// 与实例所在地址无关，可作为诊断或 FFI 的稳定指纹；不变量成立时总是 `0`：
+    ///
+    /// ```
+    /// use rust_advanced::pin::OptionalSelfRef;
+    ///
+    /// let pinned = OptionalSelfRef::new_with_ref(String::from("偏移"));
+    /// assert_eq!(pinned.ref_offset(), Some(0));
+    /// assert_eq!(OptionalSelfRef::new_no_ref(1).ref_offset(), None);
+    /// ```
+    pub fn ref_offset(&self) -> Option<isize> {
+        let base = &*self.data as *const T as *const u8 as usize;
+        // 只比较地址而不用 offset_from：被破坏的自引用可能指向别的分配，offset_from 在那时是 UB
+        self.self_ref
+            .map(|ptr| (ptr.as_ptr() as *const u8 as usize).wrapping_sub(base) as isize)
+    }
+
+    // 实现了 Drop 之后不能直接移出字段：先阻止析构（同时清除自引用），再取出 Box
+    fn into_box(self) -> Box<T> {
+        let mut this = ManuallyDrop::new(self);
+        store_self_ref(&mut this.self_ref, None);
+        // 其余拥有所有权的字段照常释放
+        drop(this.name.take());
+        // SAFETY：this 不会再被析构，data 只被读出这一次
+        unsafe { core::ptr::read(&this.data) }
+    }
+
+    // 除 swap 交换 Box 之外，集中唯一的 get_unchecked_mut：把固定的实例拆成「指向 data 的裸指针」与「self_ref 槽位」
+    //
+    // 裸指针用 addr_of_mut! 直接从 Box 取得，不经过中间引用，与所有 self_ref 的来源一致，
+    // 经它产生的修改不会使已有的 self_ref 失效；不交出 &mut Box<T>，Box 本身无法被替换或移动
+    fn as_pin_mut(self: Pin<&mut Self>) -> PinnedFields<'_, T> {
+        // SAFETY：只拆出字段，不移动实例；Box 的指针永远非空
+        unsafe {
+            let this = self.get_unchecked_mut();
+            PinnedFields {
+                data: NonNull::new_unchecked(core::ptr::addr_of_mut!(*this.data)),
+                self_ref: &mut this.self_ref,
+                generation: &mut this.generation,
+            }
+        }
+    }
+
+    /// 显示名称（通过 [`OptionalSelfRefBuilder`](super::OptionalSelfRefBuilder) 设置）
+    pub fn display_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    // 供构建器设置显示名称
+    pub(crate) fn set_display_name(&mut self, name: Option<Box<str>>) {
+        self.name = name;
+    }
+
+    /// 当前的修改代数：每次交出可变访问（`get_data_mut`、`with_mut`、`replace_data` 等）都会加一
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// 取得自引用凭证，没有自引用时返回 `None`
+    pub fn get_ref_token(&self) -> Option<RefToken<T>> {
+        self.self_ref.map(|ptr| RefToken {
+            ptr,
+            generation: self.generation,
+        })
+    }
+
+    /// 兑换凭证：凭证来自本实例、且期间数据未被修改时才返回引用
+    ///
+    /// 不解引用凭证中的指针，而是比较后经由本实例的 get_ref 返回，
+    /// 因此即使凭证来自已释放或其他的实例也不会产生悬垂引用
+    pub fn redeem(&self, token: RefToken<T>) -> Option<&T> {
+        if token.generation != self.generation || self.self_ref != Some(token.ptr) {
+            return None;
+        }
+        self.get_ref()
+    }
+
+    /// 交出固定实例的所有权，得到裸指针，用于跨 FFI 边界传递
+    ///
+    /// 与 `Box::into_raw` 相同，实例留在原来的堆地址上不被移动，自引用保持有效；
+    /// 之后必须恰好调用一次 [`from_raw`](OptionalSelfRef::from_raw) 取回，否则实例泄漏
+    ///
+    /// ```
+    /// use rust_advanced::pin::OptionalSelfRef;
+    ///
+    /// let raw = OptionalSelfRef::into_raw(OptionalSelfRef::new_with_ref(7));
+    /// // ……裸指针经由 C 代码传递后再交还……
+    /// let pinned = unsafe { OptionalSelfRef::from_raw(raw) };
+    /// assert_eq!(pinned.get_ref(), Some(&7));
+    /// ```
+    pub fn into_raw(this: Pin<Box<Self>>) -> *mut Self {
+        // SAFETY：裸指针只能经由 from_raw 重新固定，期间实例不会被移动
+        Box::into_raw(unsafe { Pin::into_inner_unchecked(this) })
+    }
+
+    /// 从 [`into_raw`](OptionalSelfRef::into_raw) 得到的裸指针取回固定实例的所有权
+    ///
+    /// # Safety
+    /// - `ptr` 必须来自同类型的 `into_raw`，且之后没有被取回过（每个指针只能取回一次）；
+    /// - 持有裸指针期间不得移动或释放实例，也不得经由它交出 `&mut Self`
+    pub unsafe fn from_raw(ptr: *mut Self) -> Pin<Box<Self>> {
+        // SAFETY：由调用方保证 ptr 来自 into_raw，实例自那以后一直固定在原地
+        unsafe { Pin::new_unchecked(Box::from_raw(ptr)) }
+    }
+}
+
+// as_pin_mut 拆出的字段
+struct PinnedFields<'a, T: ?Sized> {
+    data: NonNull<T>,
+    self_ref: &'a mut Option<NonNull<T>>,
+    generation: &'a mut u64,
+}
+
+impl<T, S> OptionalSelfRef<T, S> {
+    /// 仅供演示与测试：把自引用改成悬垂指针，用来验证 debug 构建下访问时的断言
+    ///
+    /// 只在 debug 构建且启用 `test-utils` feature（或本库自身的单元测试）时编译，不属于公开 API
+    ///
+    /// # Safety
+    /// 调用后不得在 release 构建中访问自引用（`get_ref`、`with_ref` 等），否则解引用悬垂指针
+    #[doc(hidden)]
+    #[cfg(all(debug_assertions, any(test, feature = "test-utils")))]
+    pub unsafe fn debug_corrupt_self_ref(self: Pin<&mut Self>) {
+        store_self_ref(self.as_pin_mut().self_ref, Some(NonNull::dangling()));
+    }
+
+    /// 取出数据：仅在没有自引用时成功，否则把容器原样交还，由调用方决定如何处理
+    ///
+    /// 有自引用的实例只能以 `Pin<Box<_>>` 存在（[`into_unpinned`](OptionalSelfRef::into_unpinned)
+    /// 会拒绝解除固定），因此按值持有的实例实际上总能成功；固定的实例请用 `into_data`
+    ///
+    /// 数据可能曾经以 `Pin<&mut T>` 交出（`get_data_mut`、`Future`），移出要求 `T: Unpin`：
+    ///
+    /// ```compile_fail
+    /// use core::marker::PhantomPinned;
+    /// use rust_advanced::pin::OptionalSelfRef;
+    ///
+    /// let _ = OptionalSelfRef::new_no_ref(PhantomPinned).take_data();
+    /// ```
+    pub fn take_data(self) -> Result<T, Self>
+    where
+        T: Unpin,
+    {
+        if self.self_ref.is_some() {
+            return Err(self);
+        }
+        Ok(*self.into_box())
+    }
+}
+
+impl<T: ?Sized, S> OptionalSelfRef<T, S> {
+    /// 遍历数据中的元素（`&T: IntoIterator`，如 `Vec<U>`、`[U]`、`HashMap<K, V>`）
+    ///
+    /// 只借出共享引用，数据所在的 Box 地址不变，固定状态下遍历同样安全
+    pub fn iter<'a>(&'a self) -> <&'a T as IntoIterator>::IntoIter
+    where
+        &'a T: IntoIterator,
+    {
+        (&*self.data).into_iter()
+    }
+}
+
+impl<T, U> OptionalSelfRef<(T, U), WithRef> {
+    /// 把两份数据组合成元组放入同一个 Box，并建立指向整个元组的自引用
+    pub fn zip(a: T, b: U) -> Pin<Box<Self>> {
+        Self::new_with_ref((a, b))
+    }
+}
+
+impl<T, U, S> OptionalSelfRef<(T, U), S> {
+    /// 经由自引用读取元组的第一个元素，没有自引用时返回 `None`
+    ///
+    /// 只保存了指向整个元组的一个指针，元素地址在它的基础上按字段投影得到
+    /// （元组字段的偏移由编译器决定，不一定按声明顺序排列）：
+    ///
+    /// ```
+    /// use rust_advanced::pin::OptionalSelfRef;
+    ///
+    /// let pair = OptionalSelfRef::zip(7, String::from("七"));
+    /// assert_eq!(pair.ref_to_first(), Some(&7));
+    /// assert_eq!(pair.ref_to_second().map(String::as_str), Some("七"));
+    /// ```
+    pub fn ref_to_first(&self) -> Option<&T> {
+        self.get_ref().map(|pair| &pair.0)
+    }
+
+    /// 经由自引用读取元组的第二个元素，没有自引用时返回 `None`
+    pub fn ref_to_second(&self) -> Option<&U> {
+        self.get_ref().map(|pair| &pair.1)
+    }
+}
+
+impl<U, S> OptionalSelfRef<Vec<U>, S> {
+    /// 元素个数（委托给内部的 Vec）
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// 是否没有元素
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+// 析构时先清除自引用，再释放数据：data 释放期间不存在指向它的自引用
+impl<T: ?Sized, S> Drop for OptionalSelfRef<T, S> {
+    fn drop(&mut self) {
+        store_self_ref(&mut self.self_ref, None);
+    }
+}
+
+/// 当前存活的、已建立自引用的 `OptionalSelfRef` 实例个数
+///
+/// 只在 debug 构建中计数：建立自引用时加一，清除自引用或析构时减一；
+/// release 构建不维护计数，始终返回 0。泄漏的实例永远不会析构，计数也就不会回落：
+///
+/// ```
+/// use rust_advanced::pin::{live_self_ref_count, OptionalSelfRef};
+///
+/// let before = live_self_ref_count();
+/// drop(OptionalSelfRef::new_with_ref(1));
+/// assert_eq!(live_self_ref_count(), before);
+///
+/// // 故意泄漏：debug 构建下计数多出一个，release 构建下仍为 0
+/// Box::leak(Box::new(OptionalSelfRef::new_with_ref(2)));
+/// let count = live_self_ref_count();
+/// assert!(count == before + 1 || count == 0);
+/// ```
+pub fn live_self_ref_count() -> usize {
+    #[cfg(debug_assertions)]
+    return LIVE_SELF_REFS.load(Ordering::Relaxed);
+    #[cfg(not(debug_assertions))]
+    0
+}
+
+#[cfg(debug_assertions)]
+static LIVE_SELF_REFS: AtomicUsize = AtomicUsize::new(0);
+
+// 所有对 self_ref 的改写都经过这里，debug 构建下据此维护存活计数
+fn store_self_ref<T: ?Sized>(slot: &mut Option<NonNull<T>>, new: Option<NonNull<T>>) {
+    #[cfg(debug_assertions)]
+    match (slot.is_some(), new.is_some()) {
+        (false, true) => {
+            LIVE_SELF_REFS.fetch_add(1, Ordering::Relaxed);
+        }
+        (true, false) => {
+            LIVE_SELF_REFS.fetch_sub(1, Ordering::Relaxed);
+        }
+        _ => {}
+    }
+    *slot = new;
+}
+
+// 标准转换：数据（或已有的 Box）直接成为无自引用的实例
+impl<T> From<T> for OptionalSelfRef<T, NoRef> {
+    fn from(data: T) -> Self {
+        OptionalSelfRef::new_no_ref(data)
+    }
+}
+
+impl<T: ?Sized> From<Box<T>> for OptionalSelfRef<T, NoRef> {
+    fn from(data: Box<T>) -> Self {
+        OptionalSelfRef::from_boxed(data)
+    }
+}
+
+// 默认值为无自引用的实例，可在泛型代码中按需构造
+impl<T: Default> Default for OptionalSelfRef<T, NoRef> {
+    fn default() -> Self {
+        OptionalSelfRef::new_no_ref(T::default())
+    }
+}
+
+impl<T: Default> OptionalSelfRef<T, WithRef> {
+    /// 以 `T::default()` 创建固定的实例，并已建立自引用
+    pub fn default_pinned() -> Pin<Box<Self>> {
+        Self::new_with_ref(T::default())
+    }
+}
+
+// 无自引用时没有需要修正的指针，直接克隆数据即可
+impl<T: Clone> Clone for OptionalSelfRef<T, NoRef> {
+    fn clone(&self) -> Self {
+        OptionalSelfRef::new_no_ref((*self.data).clone())
+    }
+}
+
+impl<T: Clone> OptionalSelfRef<T, WithRef> {
+    /// 克隆为新的固定实例：数据克隆到新的 Box 中，
+    /// 若原实例有自引用，则针对克隆体自己的数据重新建立，绝不沿用原实例的指针
+    pub fn clone_pinned(self: Pin<&Self>) -> Pin<Box<Self>> {
+        let mut cloned = Self::new_pinned((*self.data).clone());
+        if self.self_ref.is_some() {
+            cloned.as_mut().init_self_ref();
+        }
+        cloned
+    }
+}
+
+// 比较、排序、哈希只看数据：两个独立构造的实例 self_ref 地址必然不同，指针身份刻意不参与；
+// 有无自引用（包括 NoRef 与 WithRef 之间）也不影响结果，数据相同即相等
+impl<T: PartialEq + ?Sized, S, S2> PartialEq<OptionalSelfRef<T, S2>> for OptionalSelfRef<T, S> {
+    fn eq(&self, other: &OptionalSelfRef<T, S2>) -> bool {
+        *self.data == *other.data
+    }
+}
+
+impl<T: Eq + ?Sized, S> Eq for OptionalSelfRef<T, S> {}
+
+impl<T: PartialOrd + ?Sized, S, S2> PartialOrd<OptionalSelfRef<T, S2>> for OptionalSelfRef<T, S> {
+    fn partial_cmp(&self, other: &OptionalSelfRef<T, S2>) -> Option<core::cmp::Ordering> {
+        (*self.data).partial_cmp(&*other.data)
+    }
+}
+
+impl<T: Ord + ?Sized, S> Ord for OptionalSelfRef<T, S> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (*self.data).cmp(&*other.data)
+    }
+}
+
+impl<T: core::hash::Hash + ?Sized, S> core::hash::Hash for OptionalSelfRef<T, S> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        (*self.data).hash(state)
+    }
+}
+
+// 转发 Future：poll 经由结构化投影 get_data_mut 得到 Pin<&mut F>
+// 只为 WithRef 实现：NoRef 是 Unpin 且会交出 &mut F，若也能 poll，
+// 调用方就可以在 poll 之后移动（mem::swap）已经开始执行的 F
+impl<F: Future + ?Sized> Future for OptionalSelfRef<F, WithRef> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        self.get_data_mut().poll(cx)
+    }
+}
+
+// 布局检查：Option<NonNull<T>> 与裸指针大小相同（空值优化生效），
+// 因此 OptionalSelfRef 只占 Box、一个指针、修改代数与显示名称的空间
+const _: () = assert!(
+    core::mem::size_of::<Option<NonNull<u8>>>() == core::mem::size_of::<*const u8>()
+);
+const _: () = assert!(
+    core::mem::size_of::<OptionalSelfRef<u8>>()
+        == core::mem::size_of::<Box<u8>>()
+            + core::mem::size_of::<*const u8>()
+            + core::mem::size_of::<u64>()
+            + core::mem::size_of::<Option<Box<str>>>()
+);
+
+#[cfg(feature = "serde")]
+mod serde_support {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    // 序列化格式：只保存数据和「是否有自引用」，绝不保存裸指针
+    #[derive(Serialize)]
+    #[serde(rename = "OptionalSelfRef")]
+    struct ReprRef<'a, T: ?Sized> {
+        data: &'a T,
+        has_self_ref: bool,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "OptionalSelfRef")]
+    struct Repr<T> {
+        data: T,
+        has_self_ref: bool,
+    }
+
+    impl<T: Serialize + ?Sized, S> Serialize for OptionalSelfRef<T, S> {
+        fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+            ReprRef {
+                data: &*self.data,
+                has_self_ref: self.self_ref.is_some(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    /// 反序列化结果：无自引用时得到可移动的实例，有自引用时得到已固定、指针已重建的实例
+    #[derive(Debug)]
+    pub enum DeserializedSelfRef<T> {
+        Movable(OptionalSelfRef<T, NoRef>),
+        Pinned(Pin<Box<OptionalSelfRef<T, WithRef>>>),
+    }
+
+    impl<T: Serialize> Serialize for DeserializedSelfRef<T> {
+        fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+            match self {
+                DeserializedSelfRef::Movable(movable) => movable.serialize(serializer),
+                DeserializedSelfRef::Pinned(pinned) => (**pinned).serialize(serializer),
+            }
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for DeserializedSelfRef<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = Repr::<T>::deserialize(deserializer)?;
+            // 有自引用时在新分配的 Box 上重新建立指针
+            Ok(if repr.has_self_ref {
+                DeserializedSelfRef::Pinned(OptionalSelfRef::new_with_ref(repr.data))
+            } else {
+                DeserializedSelfRef::Movable(OptionalSelfRef::new_no_ref(repr.data))
+            })
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_support::DeserializedSelfRef;
+
+// 以下测试预期在 `cargo miri test` 下通过（包括 `-Zmiri-strict-provenance` 与 `-Zmiri-tree-borrows`），
+// CI 的 nightly 任务会这样运行
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::string::String;
+
+    use super::{NoRef, OptionalSelfRef};
+
+    #[test]
+    fn self_ref_provenance_survives_other_field_writes() {
+        // 固定 → 经自引用读取
+        let mut pinned = OptionalSelfRef::new_with_ref(String::from("来源"));
+        let first = pinned.get_ref().unwrap() as *const String;
+        assert_eq!(pinned.get_ref().map(String::as_str), Some("来源"));
+
+        // 修改其他字段：get_data_mut 只增加修改代数，不写入数据
+        let _ = pinned.as_mut().get_data_mut();
+        assert_eq!(pinned.generation(), 1);
+        // 再次读取：self_ref 的来源没有被其他字段的写入作废
+        assert_eq!(pinned.get_ref().map(String::as_str), Some("来源"));
+
+        // 经由 as_pin_mut 推导的访问写入数据后，原有的 self_ref 仍可解引用
+        pinned.as_mut().with_mut(|s| s.push('！'));
+        assert_eq!(pinned.get_ref().map(String::as_str), Some("来源！"));
+        assert!(core::ptr::eq(pinned.get_ref().unwrap(), first));
+        assert!(pinned.is_ref_valid());
+    }
+
+    #[test]
+    fn map_data_rebuilds_self_ref_on_new_data() {
+        let words = OptionalSelfRef::new_with_ref(String::from("四个汉字"));
+        let mapped = words.map_data(|s| s.chars().count());
+        assert_eq!(mapped.get_ref(), Some(&4));
+        // 新自引用指向新实例自己的数据
+        assert!(mapped.with_data_and_ref(|data, view| view.is_some_and(|v| core::ptr::eq(data, v))));
+        assert!(mapped.is_ref_valid());
+    }
+
+    #[test]
+    fn map_data_without_self_ref_stays_without() {
+        let pinned = OptionalSelfRef::new_pinned(String::from("abc")).map_data(|s| s.len());
+        assert_eq!(pinned.with_data_and_ref(|data, view| (*data, view.is_none())), (3, true));
+
+        let movable = OptionalSelfRef::<_, NoRef>::map_data(OptionalSelfRef::new_no_ref(21), |n| n * 2);
+        assert_eq!(movable, OptionalSelfRef::new_no_ref(42));
+    }
+
+    #[test]
+    fn init_self_ref_after_pinning_points_at_data() {
+        let deferred = OptionalSelfRef::new_no_ref(String::from("延迟建立")).into_with_ref();
+        // 尚未建立自引用，固定之前仍可移动
+        let moved = deferred;
+        let mut pinned = core::pin::pin!(moved);
+        assert_eq!(pinned.get_ref(), None);
+        assert!(pinned.as_mut().init_self_ref());
+        // 已有自引用时不重复建立
+        assert!(!pinned.as_mut().init_self_ref());
+        assert!(pinned.with_data_and_ref(|data, view| view.is_some_and(|v| core::ptr::eq(data, v))));
+    }
+
+    #[test]
+    fn get_data_mut_keeps_self_ref_valid() {
+        let mut counter = OptionalSelfRef::new_with_ref(1);
+        *counter.as_mut().get_data_mut() += 41;
+        assert_eq!(counter.get_ref(), Some(&42));
+
+        let mut text = OptionalSelfRef::new_with_ref(String::from("固定"));
+        let before = text.get_ref().unwrap() as *const String;
+        text.as_mut().get_data_mut().push_str(&"的字符串".repeat(32));
+        // 只有 String 的缓冲区重新分配，Box<String> 本身不动
+        assert!(core::ptr::eq(text.get_ref().unwrap(), before));
+        assert!(text.get_ref().unwrap().starts_with("固定的字符串"));
+        assert!(text.is_ref_valid());
+
+        let mut plain = OptionalSelfRef::new_no_ref(0);
+        *plain.get_data_mut() = 10;
+        assert_eq!(plain, OptionalSelfRef::new_no_ref(10));
+    }
+
+    #[test]
+    fn clone_pinned_points_into_its_own_data() {
+        let original = OptionalSelfRef::new_with_ref(String::from("被克隆的数据"));
+        let cloned = original.as_ref().clone_pinned();
+        assert!(cloned.with_data_and_ref(|data, view| view.is_some_and(|v| core::ptr::eq(data, v))));
+        assert!(!core::ptr::eq(cloned.get_ref().unwrap(), original.get_ref().unwrap()));
+        drop(original);
+        assert_eq!(cloned.get_ref().map(String::as_str), Some("被克隆的数据"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_set_looks_up_by_data() {
+        use std::collections::HashSet;
+
+        let a = OptionalSelfRef::new_with_ref(String::from("a"));
+        let b = OptionalSelfRef::new_with_ref(String::from("b"));
+        let mut set = HashSet::new();
+        set.insert(&*a);
+        set.insert(&*b);
+        // 自引用状态不参与比较：没有自引用的固定实例也能查到
+        assert!(set.contains(&*OptionalSelfRef::new_pinned(String::from("a"))));
+        assert!(!set.contains(&*OptionalSelfRef::new_pinned(String::from("c"))));
+    }
+
+    #[test]
+    fn btree_set_orders_by_data() {
+        let ordered: BTreeSet<_> = [3, 1, 2].into_iter().map(OptionalSelfRef::new_no_ref).collect();
+        assert!(ordered.contains(&OptionalSelfRef::new_no_ref(2)));
+        assert_eq!(ordered.first(), Some(&OptionalSelfRef::new_no_ref(1)));
+        assert_eq!(ordered.last(), Some(&OptionalSelfRef::new_no_ref(3)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn pinned_instance_crosses_threads() {
+        let shared = OptionalSelfRef::new_with_ref(String::from("跨线程的自引用"));
+        let handle = std::thread::spawn(move || {
+            let read = shared.get_ref().cloned();
+            (shared, read)
+        });
+        let (shared, read) = handle.join().unwrap();
+        assert_eq!(read.as_deref(), Some("跨线程的自引用"));
+        // 固定的 Box 整体往返线程，自引用仍指向自身数据
+        assert!(shared.is_ref_valid());
+        assert_eq!(shared.get_ref().map(String::as_str), Some("跨线程的自引用"));
+    }
+
+    #[test]
+    fn take_data_and_into_data() {
+        let taken = OptionalSelfRef::new_no_ref(String::from("可移动的数据")).take_data();
+        assert_eq!(taken.ok().as_deref(), Some("可移动的数据"));
+
+        // 有自引用时拒绝解除固定，原样交还固定的 Box
+        let original = String::from("固定实例的数据");
+        let pinned = OptionalSelfRef::into_unpinned(OptionalSelfRef::new_with_ref(original.clone()))
+            .unwrap_err();
+        assert_eq!(pinned.get_ref(), Some(&original));
+        // 固定的实例整体消费后取出数据
+        assert_eq!(OptionalSelfRef::into_data(pinned), original);
+
+        let unpinned = OptionalSelfRef::into_unpinned(OptionalSelfRef::new_pinned(3)).unwrap();
+        assert_eq!(unpinned.take_data().ok(), Some(3));
+    }
+
+    #[test]
+    fn replace_data_keeps_address() {
+        let mut replaced = OptionalSelfRef::new_with_ref(String::from("旧数据"));
+        let before = replaced.get_ref().unwrap() as *const String;
+        let old = replaced.as_mut().replace_data(String::from("新数据"));
+        assert_eq!(old, "旧数据");
+        assert_eq!(replaced.get_ref().map(String::as_str), Some("新数据"));
+        // 新值写入原来的 Box，替换前后地址相同
+        assert!(core::ptr::eq(replaced.get_ref().unwrap(), before));
+        assert!(replaced.is_ref_valid());
+    }
+
+    #[test]
+    fn ref_token_goes_stale_after_mutation() {
+        let mut tracked = OptionalSelfRef::new_with_ref(String::from("第一版"));
+        let token = tracked.get_ref_token().unwrap();
+        assert_eq!(tracked.redeem(token).map(String::as_str), Some("第一版"));
+
+        tracked.as_mut().replace_data(String::from("第二版"));
+        assert_eq!(tracked.generation(), 1);
+        // 修改代数变了：旧凭证兑换失败
+        assert_eq!(tracked.redeem(token), None);
+        let fresh = tracked.get_ref_token().unwrap();
+        assert_eq!(tracked.redeem(fresh).map(String::as_str), Some("第二版"));
+
+        // 其他实例的凭证同样无法兑换
+        let other = OptionalSelfRef::new_with_ref(String::from("第二版"));
+        assert_eq!(tracked.redeem(other.get_ref_token().unwrap()), None);
+        assert!(OptionalSelfRef::new_pinned(1).get_ref_token().is_none());
+    }
+}
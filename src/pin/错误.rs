@@ -0,0 +1,125 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+/// 区间校验失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeError {
+    /// 区间超出数据长度（或起点大于终点）
+    OutOfBounds { start: usize, end: usize, len: usize },
+    /// 下标不在 UTF-8 字符边界上
+    NotCharBoundary { index: usize },
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::OutOfBounds { start, end, len } => {
+                write!(f, "区间 {}..{} 超出长度 {}", start, end, len)
+            }
+            RangeError::NotCharBoundary { index } => {
+                write!(f, "下标 {} 不在字符边界上", index)
+            }
+        }
+    }
+}
+
+// no_std 下没有 std::error::Error，只保留 Display
+#[cfg(feature = "std")]
+impl Error for RangeError {}
+
+/// 构造 `SelfRef` 失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelfRefError {
+    /// 内容为空字符串
+    Empty,
+}
+
+impl fmt::Display for SelfRefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelfRefError::Empty => f.write_str("内容不能为空字符串"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for SelfRefError {}
+
+/// `OptionalSelfRefBuilder` 的配置无效
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// 要求建立自引用，却调用了 `build_movable`：可移动的实例不能持有自引用
+    SelfRefNeedsPinning,
+    /// 显示名称为空字符串
+    EmptyDisplayName,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::SelfRefNeedsPinning => {
+                f.write_str("要求建立自引用的实例必须固定，请改用 build_pinned")
+            }
+            BuildError::EmptyDisplayName => f.write_str("显示名称不能为空"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BuildError {}
+
+/// `OptionalSelfRef::rebind_ref` 的目标无效，地址以数值形式保存
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebindError {
+    /// 目标不在自身数据的分配之内（如栈上的局部变量、其他实例的数据）
+    OutsideData { target: usize, data: usize },
+    /// 目标落在数据内部却不是数据本身：自引用的类型是 `&T`，只能指向完整的数据
+    NotWholeData { offset: usize, len: usize },
+}
+
+impl fmt::Display for RebindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RebindError::OutsideData { target, data } => {
+                write!(f, "目标 {:#x} 不在数据 {:#x} 的分配之内", target, data)
+            }
+            RebindError::NotWholeData { offset, len } => {
+                write!(f, "目标（偏移 {}，长度 {}）只是数据的一部分", offset, len)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for RebindError {}
+
+/// 自引用不变量被破坏：由 `verify_invariant` 返回，地址以数值形式保存
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// `OptionalSelfRef` 的自引用没有指向自身数据
+    SelfRefMismatch { expected: usize, actual: usize },
+    /// `SelfRef` / `SelfRefBytes` 的 ptr 起始地址与缓冲区不一致
+    AddressMismatch { expected: usize, actual: usize },
+    /// `SelfRef` / `SelfRefBytes` 的 ptr 长度与缓冲区不一致
+    LengthMismatch { expected: usize, actual: usize },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::SelfRefMismatch { expected, actual } => {
+                write!(f, "自引用应指向 {:#x}，实际指向 {:#x}", expected, actual)
+            }
+            InvariantViolation::AddressMismatch { expected, actual } => {
+                write!(f, "ptr 应指向缓冲区 {:#x}，实际指向 {:#x}", expected, actual)
+            }
+            InvariantViolation::LengthMismatch { expected, actual } => {
+                write!(f, "ptr 长度应为 {}，实际为 {}", expected, actual)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for InvariantViolation {}
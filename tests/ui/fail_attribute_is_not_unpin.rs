@@ -0,0 +1,16 @@
+use rust_advanced::self_referential;
+
+#[self_referential]
+struct Doc {
+    #[payload]
+    text: String,
+    #[self_ref]
+    ptr: *const String,
+}
+
+fn assert_unpin<T: Unpin>() {}
+
+fn main() {
+    // 插入的 PhantomPinned 让类型不再是 Unpin，不能从 Pin 中移出
+    assert_unpin::<Doc>();
+}
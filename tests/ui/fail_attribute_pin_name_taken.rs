@@ -0,0 +1,12 @@
+use rust_advanced::self_referential;
+
+#[self_referential]
+struct Doc {
+    #[payload]
+    text: String,
+    #[self_ref]
+    ptr: *const String,
+    _pin: u8,
+}
+
+fn main() {}
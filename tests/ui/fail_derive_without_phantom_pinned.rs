@@ -0,0 +1,11 @@
+use rust_advanced::SelfReferential;
+
+#[derive(SelfReferential)]
+struct Doc {
+    #[payload]
+    text: String,
+    #[self_ref]
+    ptr: *const String,
+}
+
+fn main() {}
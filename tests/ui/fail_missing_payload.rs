@@ -0,0 +1,12 @@
+use core::marker::PhantomPinned;
+use rust_advanced::SelfReferential;
+
+#[derive(SelfReferential)]
+struct Doc {
+    text: String,
+    #[self_ref]
+    ptr: *const String,
+    _pin: PhantomPinned,
+}
+
+fn main() {}
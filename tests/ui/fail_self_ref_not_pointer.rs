@@ -0,0 +1,13 @@
+use core::marker::PhantomPinned;
+use rust_advanced::SelfReferential;
+
+#[derive(SelfReferential)]
+struct Doc {
+    #[payload]
+    text: String,
+    #[self_ref]
+    ptr: Option<core::ptr::NonNull<String>>,
+    _pin: PhantomPinned,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use core::marker::PhantomPinned;
+use rust_advanced::SelfReferential;
+
+#[derive(SelfReferential)]
+#[self_ref(krate = rust_advanced)]
+struct Doc {
+    #[payload]
+    text: String,
+    #[self_ref]
+    ptr: *const String,
+    _pin: PhantomPinned,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use rust_advanced::self_referential;
+
+#[self_referential]
+struct Doc<T> {
+    #[payload]
+    values: Vec<T>,
+    #[self_ref]
+    ptr: *mut Vec<T>,
+}
+
+fn main() {
+    let doc = Doc::new(vec![1, 2, 3]);
+    assert_eq!(doc.get_ref(), &[1, 2, 3]);
+    assert!(core::ptr::eq(doc.ptr, &doc.values));
+    // 插入的 PhantomPinned 字段以默认值初始化
+    let _: &core::marker::PhantomPinned = &doc._pin;
+}
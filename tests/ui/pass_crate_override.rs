@@ -0,0 +1,30 @@
+use core::marker::PhantomPinned;
+use rust_advanced as ra;
+
+// 通过别名引用本库时显式指定 crate 路径
+#[derive(ra::SelfReferential)]
+#[self_ref(crate = ra)]
+struct Doc {
+    #[payload]
+    text: String,
+    #[self_ref]
+    ptr: *const String,
+    _pin: PhantomPinned,
+}
+
+#[ra::self_referential]
+#[self_ref(crate = ::rust_advanced)]
+struct Note {
+    #[payload]
+    text: String,
+    #[self_ref]
+    ptr: *const String,
+}
+
+fn main() {
+    let doc = Doc::new(String::from("别名"));
+    assert_eq!(doc.get_ref(), "别名");
+    let note = Note::new(String::from("绝对路径"));
+    assert_eq!(note.get_ref(), "绝对路径");
+    assert!(core::ptr::eq(note.ptr, &note.text));
+}
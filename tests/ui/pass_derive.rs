@@ -0,0 +1,19 @@
+use core::marker::PhantomPinned;
+use rust_advanced::SelfReferential;
+
+#[derive(SelfReferential)]
+struct Doc {
+    #[payload]
+    text: String,
+    #[self_ref]
+    ptr: *const String,
+    views: u32,
+    _pin: PhantomPinned,
+}
+
+fn main() {
+    let doc = Doc::new(String::from("派生宏"));
+    assert_eq!(doc.get_ref(), "派生宏");
+    assert!(core::ptr::eq(doc.ptr, &doc.text));
+    assert_eq!(doc.views, 0);
+}
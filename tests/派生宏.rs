@@ -0,0 +1,11 @@
+//! `#[derive(SelfReferential)]` / `#[self_referential]` 的编译期行为：
+//! `ui/pass_*.rs` 必须编译通过并运行成功，`ui/fail_*.rs` 必须以对应 `.stderr` 中的错误编译失败
+//!
+//! 修改宏的报错信息后用 `TRYBUILD=overwrite cargo test --features derive --test 派生宏` 重新生成 `.stderr`
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass_*.rs");
+    t.compile_fail("tests/ui/fail_*.rs");
+}
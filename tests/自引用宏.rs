@@ -0,0 +1,43 @@
+//! `selfref!` 展开后的 API：在库外调用，同时验证 `$crate` 路径
+
+use std::pin::Pin;
+
+use rust_advanced::selfref;
+
+selfref!(MyBuf, Vec<u8>);
+selfref!(
+    /// 属性与可见性原样保留
+    #[derive(Debug)]
+    pub Label,
+    String
+);
+
+#[test]
+fn new_points_into_own_data_field() {
+    let buf = MyBuf::new(vec![1, 2, 3]);
+    assert_eq!(buf.get_ref(), &[1, 2, 3]);
+    // 自引用指向结构体自身的 data 字段，而不是某份拷贝
+    assert!(std::ptr::eq(buf.get_ref(), &buf.data));
+    assert_eq!(buf.ptr.map(|p| p.as_ptr().cast_const()), Some(&buf.data as *const Vec<u8>));
+}
+
+#[test]
+fn update_resyncs_pointer() {
+    let mut buf = MyBuf::new(vec![1]);
+    let field = &buf.data as *const Vec<u8>;
+    buf.as_mut().update(vec![4, 5, 6, 7]);
+    assert_eq!(buf.get_ref(), &[4, 5, 6, 7]);
+    // 字段地址随实例固定不变，自引用重新指向它
+    assert!(std::ptr::eq(buf.get_ref(), field));
+}
+
+#[test]
+fn moving_the_box_keeps_self_ref_valid() {
+    let boxes: Vec<Pin<Box<Label>>> = (0..3).map(|i| Label::new(format!("标签{}", i))).collect();
+    let moved: Vec<_> = boxes.into_iter().rev().collect();
+    for (label, expected) in moved.iter().zip(["标签2", "标签1", "标签0"]) {
+        assert_eq!(label.get_ref(), expected);
+        assert!(std::ptr::eq(label.get_ref(), &label.data));
+    }
+    assert!(format!("{:?}", moved[0]).contains("标签2"));
+}